@@ -0,0 +1,51 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[macro_use]
+extern crate lazy_static;
+extern crate log;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+mod solver;
+
+pub use solver::parse::{one_per_line, parse_lines, parse_puzzles, ParseError};
+pub use solver::registry::{FnStrategy, ForcingStrategy, Registry, Strategy};
+pub use solver::step::Deduction;
+pub use solver::trace::{Step, Trace};
+pub use solver::{Cell, Game, Grid, Point, MAX_CHAIN};
+
+// Maximum difficulty level found on solving.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Level {
+    Easy,
+    Standard,
+    Hard,
+    Expert,
+    Extreme,
+    Forcing,
+}
+
+static COLORIZE: AtomicBool = AtomicBool::new(false);
+
+// set_colorize turns the ANSI colors used by Grid::display on or off.
+pub fn set_colorize(on: bool) {
+    COLORIZE.store(on, Ordering::Relaxed);
+}
+
+fn colorize() -> bool {
+    COLORIZE.load(Ordering::Relaxed)
+}
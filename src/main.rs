@@ -15,29 +15,13 @@
  */
 
 extern crate clap;
-#[macro_use]
-extern crate lazy_static;
-extern crate log;
 
 use clap::{crate_authors, crate_version, value_t, values_t, App, Arg};
 use rayon::prelude::*;
-use solver::Grid;
 use std::collections::HashSet;
 use std::fs;
-
-mod solver;
-
-// Maximum difficulty level found on solving.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
-pub enum Level {
-    Easy,
-    Standard,
-    Hard,
-    Expert,
-    Extreme,
-}
-
-static mut COLORIZE: bool = false;
+use std::io::Write;
+use sudoku::{one_per_line, parse_lines, parse_puzzles, set_colorize, Grid, Level, Registry, Trace, MAX_CHAIN};
 
 fn main() {
     env_logger::init();
@@ -53,7 +37,13 @@ fn main() {
                 .value_name("FILE")
                 .takes_value(true)
                 .multiple(true)
-                .help("Read standard-format sudoku puzzle initial layout files"),
+                .help("Read sudoku puzzle initial layout files (one puzzle per line in the strict format, or 9x9 blocks, .sdk or .ss)"),
+        )
+        .arg(
+            Arg::with_name("marks")
+                .short("m")
+                .long("marks")
+                .help("Read each input file as a pencil-mark (candidate) grid, in the boxed text or 729 character form"),
         )
         .arg(
             Arg::with_name("level0")
@@ -103,6 +93,49 @@ fn main() {
                 .takes_value(true)
                 .help("Number of attempts to generate a puzzle"),
         )
+        .arg(
+            Arg::with_name("maxchain")
+                .short("l")
+                .long("max-chain")
+                .value_name("LENGTH")
+                .takes_value(true)
+                .help("Maximum number of strong links (cells in an xy-chain) in a chain when solving the input puzzles"),
+        )
+        .arg(
+            Arg::with_name("uniqueness")
+                .short("u")
+                .long("uniqueness")
+                .help("Use strategies that assume the input puzzles have a single solution"),
+        )
+        .arg(
+            Arg::with_name("exclude")
+                .short("x")
+                .long("exclude")
+                .value_name("STRATEGY")
+                .takes_value(true)
+                .multiple(true)
+                .help("Do not use the named strategies when solving the input puzzles"),
+        )
+        .arg(
+            Arg::with_name("hint")
+                .short("n")
+                .long("next-step")
+                .help("Show the next deduction for each input puzzle instead of solving it"),
+        )
+        .arg(
+            Arg::with_name("trace")
+                .short("t")
+                .long("trace")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write a trace of every deduction made solving the input puzzles to a file"),
+        )
+        .arg(
+            Arg::with_name("forcing")
+                .short("f")
+                .long("forcing")
+                .help("Use forcing chains and nishio as a last resort before searching when solving the input puzzles"),
+        )
         .arg(Arg::with_name("colorize").short("c").long("colorize").help("Colorize the output using ANDI escapes"))
         .after_help(format!("build timestamp: {}\ngit hash: {}", env!("BUILD_TIMESTAMP"), env!("GIT_HASH")).as_str())
         .get_matches();
@@ -114,11 +147,27 @@ fn main() {
     // let level_4_count = value_t!(matches, "level4", u32).unwrap_or(0);
     let inputs = values_t!(matches, "inputs", String).unwrap_or_else(|_e| Vec::new());
     let max_attempts = value_t!(matches, "attempts", u32).unwrap_or(100);
-    unsafe {
-        COLORIZE = matches.is_present("colorize");
+    let assume_unique = matches.is_present("uniqueness");
+    let max_chain = value_t!(matches, "maxchain", usize).unwrap_or(MAX_CHAIN);
+    let forcing = matches.is_present("forcing");
+    let hint = matches.is_present("hint");
+    let marks = matches.is_present("marks");
+    let mut trace_file = matches.value_of("trace").map(|name| match fs::File::create(name) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("cannot open \"{}\" for writing: {}", name, e);
+            std::process::exit(1);
+        }
+    });
+    let mut registry = if forcing { Registry::with_forcing() } else { Registry::default() };
+    for name in values_t!(matches, "exclude", String).unwrap_or_else(|_e| Vec::new()) {
+        if registry.remove(&name).is_none() {
+            eprintln!("unknown strategy \"{}\"", name);
+        }
     }
+    set_colorize(matches.is_present("colorize"));
 
-    if inputs.len() > 0 {
+    if !inputs.is_empty() {
         // Handle -i files.
         let mut all = 0;
         let mut sol = 0;
@@ -126,16 +175,56 @@ fn main() {
             let lines = fs::read_to_string(&input);
             match lines {
                 Ok(lines) => {
-                    for line in lines.lines() {
+                    let puzzles = if marks {
+                        vec![(1, Grid::parse_candidates(&lines))]
+                    } else if one_per_line(&lines) {
+                        parse_lines(&lines)
+                    } else {
+                        parse_puzzles(&lines)
+                    };
+                    for (n, parsed) in puzzles {
+                        let mut grid = match parsed {
+                            Ok(grid) => grid,
+                            Err(e) => {
+                                eprintln!("skipping puzzle at line {} of \"{}\": {}", n, &input, e);
+                                continue;
+                            }
+                        };
+                        let line = if marks { grid.encode_candidates() } else { grid.encode() };
                         all += 1;
                         println!("Encoded: {}", line);
-                        let mut grid = Grid::parse_grid(&line);
+                        grid.assume_unique = assume_unique;
+                        grid.max_chain = max_chain;
                         grid.display();
 
+                        if hint {
+                            match grid.next_step_with(&registry) {
+                                Some(step) => {
+                                    println!("next step: {} ({:?})", step.strategy, step.level);
+                                    for reason in step.reasons {
+                                        println!("  {}", reason);
+                                    }
+                                    println!("  pattern {:?}", step.pattern);
+                                    println!("  digits {:?}", step.digits);
+                                    println!("  places {:?}", step.placements);
+                                    println!("  removes {:?}", step.eliminations);
+                                }
+                                None => println!("no next step found"),
+                            }
+                            continue;
+                        }
+
                         let mut strategies = HashSet::<&'static str>::new();
-                        let (max_level, solved) = grid.reduce(&mut Some(&mut strategies));
+                        let mut trace = Trace::default();
+                        let (max_level, solved) = grid.reduce_with(&registry, &mut Some(&mut strategies), &mut trace_file.as_ref().map(|_| &mut trace));
                         grid.display();
 
+                        if let Some(f) = trace_file.as_mut() {
+                            if let Err(e) = write!(f, "puzzle: {}\n{}", line, trace.export()) {
+                                eprintln!("cannot write trace: {}", e);
+                            }
+                        }
+
                         let mut names: Vec<&str> = strategies.into_iter().collect();
                         names.sort();
 
@@ -144,6 +233,7 @@ fn main() {
                             println!("level: {:?}, solved, ({})", max_level, names.join(", "));
                         } else {
                             println!("level: {:?}, not solved ({})", max_level, names.join(", "));
+                            print!("{}", grid.format_candidates());
 
                             let mut solutions = Vec::<Grid>::new();
                             grid.search(&mut solutions);
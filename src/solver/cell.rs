@@ -15,7 +15,6 @@
  */
 
 #[macro_export]
-
 macro_rules! op_other {
     ($name:ident, $self_:ident, $other:ident, $body:expr) => {
         pub fn $name(&mut $self_, $other: &Cell) -> bool {
@@ -37,7 +36,7 @@ pub struct Cell(pub u16);
 lazy_static! {
     pub static ref BIT_COUNT: [u8; 1024] = {
         let mut bits: [u8; 1024] = [0; 1024];
-        for i in 0..1024 {
+        for (i, b) in bits.iter_mut().enumerate() {
             // Use Brian Kernighan's algorithm to count bits set to 1.
             let mut n = i;
             let mut count = 0;
@@ -45,7 +44,7 @@ lazy_static! {
                 n &= n - 1;
                 count += 1;
             }
-            bits[i] = count;
+            *b = count;
         }
         bits
     };
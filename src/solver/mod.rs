@@ -36,9 +36,10 @@ macro_rules! group_loop {
 }
 
 macro_rules! cell_change {
-    ($self_:ident, $res:ident, $($print:expr),+) => {
+    ($self_:ident, $res:ident, $pattern:expr, $digits:expr, $($print:expr),+) => {
         $res = true;
         info!($($print),+);
+        $crate::solver::step::note($self_, $pattern, $digits, format_args!($($print),+));
         if log_enabled!(Level::Debug) {
             $self_.display();
         }
@@ -47,9 +48,10 @@ macro_rules! cell_change {
 
 extern crate rand;
 
+use super::colorize;
 use super::Level;
-use super::COLORIZE;
-use cell::{Cell, BIT_COUNT};
+pub use cell::Cell;
+use cell::BIT_COUNT;
 use colored::control::set_override;
 use colored::Colorize;
 use rand::seq::SliceRandom;
@@ -59,11 +61,21 @@ use std::ops::RangeInclusive;
 use std::ops::{Index, IndexMut};
 
 mod cell;
+pub mod parse;
+pub mod registry;
+pub mod step;
 mod strategies;
+pub mod trace;
+
+pub use registry::Registry;
+pub use trace::Trace;
 
 const ALL_DIGITS: RangeInclusive<usize> = RangeInclusive::new(1, 9);
 
-const ALL: u16 = 0b_111111111_0;
+const ALL: u16 = 0b11_1111_1110;
+
+// The default maximum number of strong links in a chain.
+pub const MAX_CHAIN: usize = 8;
 const ROWS: usize = 9;
 const COLS: usize = 9;
 
@@ -104,8 +116,9 @@ lazy_static! {
         });
         Group { name: "row".to_string(), cells }
     };
+    static ref REGISTRY: Registry = Registry::default();
     static ref VISIBLE: [[u128; COLS]; ROWS] = {
-        let mut m = [[0 as u128; COLS]; ROWS];
+        let mut m = [[0_u128; COLS]; ROWS];
         for_all_cells!(r, c, {
             for p in &BOX.cells[box_of(r, c)] {
                 if r == p.0 && c == p.1 {
@@ -142,6 +155,10 @@ type Unit = [Point; 9];
 pub struct Grid {
     pub orig: [[bool; COLS]; ROWS],
     pub cells: Cells,
+    // assume_unique allows strategies that are only valid if the puzzle has a single solution. It is off for parsed grids, since an imported puzzle may have more than one solution.
+    pub assume_unique: bool,
+    // max_chain limits the number of strong links in the chains built by x_cycles, xy_chain and aic. A link between groups of cells counts as one, and in an xy-chain it is the number of cells.
+    pub max_chain: usize,
 }
 
 type Cells = [[Cell; COLS]; ROWS];
@@ -164,7 +181,7 @@ impl Grid {
             let mut solutions = Vec::<Grid>::new();
             grid.search(&mut solutions);
 
-            if solutions.len() == 0 {
+            if solutions.is_empty() {
                 // The grid has no solution.
                 attempts -= 1;
                 if attempts == 0 {
@@ -177,15 +194,11 @@ impl Grid {
 
             // From https://stackoverflow.com/a/7280517/96233.
 
-            grid = solutions[0].clone();
+            grid = solutions[0];
             let mut points = grid.all_points();
             points.shuffle(&mut thread_rng());
 
-            loop {
-                let curr = match points.pop() {
-                    Some(p) => p,
-                    None => break,
-                };
+            while let Some(curr) = points.pop() {
                 let old = grid[&curr];
                 grid[&curr] = Cell(ALL);
 
@@ -199,7 +212,8 @@ impl Grid {
             }
 
             // At this point, grid contains the smallest solution that is unique. Now we test the level.
-            let mut copy = grid.clone();
+            let mut copy = grid;
+            copy.assume_unique = true;
             let mut strategies = HashSet::<&'static str>::new();
             let (l, solved) = copy.reduce(&mut Some(&mut strategies));
 
@@ -222,12 +236,15 @@ impl Grid {
                 let mut s: Vec<&str> = strategies.into_iter().collect();
                 s.sort();
 
+                grid.assume_unique = true;
+                solution.assume_unique = true;
+
                 return Some(Game {
                     level: *level,
-                    clues: clues,
+                    clues,
                     strategies: s.join(", "),
                     puzzle: grid,
-                    solution: solution,
+                    solution,
                 });
             }
         }
@@ -246,12 +263,10 @@ impl Grid {
 
     // display prints a game.
     pub fn display(&self) {
-        unsafe {
-            set_override(COLORIZE);
-        }
+        set_override(colorize());
 
         let width = self.max_width() + 2;
-        let bars = HORIZ_BAR.repeat((width * 3) as usize);
+        let bars = HORIZ_BAR.repeat(width * 3);
         let line = LEFT_T.to_string() + &[bars.as_str(), bars.as_str(), bars.as_str()].join(PLUS) + RIGHT_T;
         print!("\t   ");
         for d in 0..9 {
@@ -346,44 +361,36 @@ impl Grid {
         width
     }
 
-    // parse_grid parses a string of digits and dots into a game structure containing two matrices: the orig matrix contains a 'true' where that cell was set in the start-up puzzle and curr contains u16's where the bits are set if that digit is valid. It panics on any illegal input.
-    pub fn parse_grid(input: &str) -> Grid {
-        let bytes = input.as_bytes();
-        let mut orig: [[bool; COLS]; ROWS] = Default::default();
-        let mut cells = [[Cell(0); COLS]; ROWS];
-        for_all_cells!(r, c, {
-            let chr = bytes[r * 9 + c];
-            if chr == b'.' {
-                cells[r][c].0 |= ALL;
-            } else {
-                let digit: u16 = match atoi::ascii_to_digit(chr) {
-                    Some(digit) => digit,
-                    None => panic!("illegal character in input grid: {} (\"{}\")", &input, chr),
-                };
-                orig[r][c] = true;
-                cells[r][c].0 |= 1 << digit;
-            }
-        });
-        Grid { orig: orig, cells: cells }
-    }
-
     // randomize generates a random grid.
     pub fn randomize() -> Grid {
         let mut cells = [[Cell(ALL); COLS]; ROWS];
         let mut rng = rand::thread_rng();
         let mut digits: Vec<u16> = (1..=9).collect();
         digits.shuffle(&mut thread_rng());
-        let mut index = 0;
-        for p in &BOX.cells[rng.gen_range(0, 9)] {
+        for (index, p) in BOX.cells[rng.gen_range(0, 9)].iter().enumerate() {
             cells[p.0][p.1] = Cell(1 << digits[index]);
-            index += 1;
         }
 
-        Grid { orig: [[false; COLS]; ROWS], cells }
+        Grid {
+            orig: [[false; COLS]; ROWS],
+            cells,
+            assume_unique: false,
+            max_chain: MAX_CHAIN,
+        }
     }
 
-    // reduce reduces all cells to the minimum number of candidates using only logical operations (no brute-froce search) and returns the highest level of operation used and a flag indicating if the puzzle is solved.
+    // reduce reduces all cells to the minimum number of candidates using only logical operations (no brute-froce search) and returns the highest level of operation used and a flag indicating if the puzzle is solved. It uses the built-in strategies.
     pub fn reduce(&mut self, strategies: &mut Option<&mut HashSet<&'static str>>) -> (Level, bool) {
+        self.reduce_with(&REGISTRY, strategies, &mut None)
+    }
+
+    // reduce_with is reduce using the strategies of a registry, optionally recording a trace of every deduction. After any strategy changes the grid, it starts again with the first strategy of the first tier.
+    pub fn reduce_with(&mut self, registry: &Registry, strategies: &mut Option<&mut HashSet<&'static str>>, trace: &mut Option<&mut Trace>) -> (Level, bool) {
+        self.reduce_to(registry, strategies, trace, Level::Forcing)
+    }
+
+    // reduce_to is reduce_with using only the strategies of the registry up to the max level.
+    fn reduce_to(&mut self, registry: &Registry, strategies: &mut Option<&mut HashSet<&'static str>>, trace: &mut Option<&mut Trace>, max: Level) -> (Level, bool) {
         if self.empty_cell() {
             return (Level::Easy, false);
         }
@@ -393,54 +400,35 @@ impl Grid {
             if self.solved() {
                 return (max_level, true);
             }
-            if self.reduce_level(
-                &mut max_level,
-                &Level::Easy,
-                strategies,
-                vec![
-                    (Grid::naked_single, "naked_single"),
-                    (Grid::hidden_single, "hidden_single"),
-                    (Grid::naked_pair, "naked_pair"),
-                    (Grid::naked_triple, "naked_triple"),
-                    (Grid::hidden_pair, "hidden_pair"),
-                    (Grid::hidden_triple, "hidden_triple"),
-                    (Grid::hidden_quad, "hidden_quad"),
-                    (Grid::pointing_line, "pointing_line"),
-                    (Grid::box_line, "box_line"),
-                ],
-            ) {
-                continue;
-            }
-            if self.reduce_level(&mut max_level, &Level::Standard, strategies, vec![(Grid::x_wing, "x_wing"), (Grid::y_wing, "y_wing")]) {
-                continue;
-            }
-            if self.reduce_level(&mut max_level, &Level::Hard, strategies, vec![]) {
-                continue;
-            }
-            if self.reduce_level(&mut max_level, &Level::Expert, strategies, vec![]) {
-                continue;
-            }
-            if self.reduce_level(&mut max_level, &Level::Extreme, strategies, vec![]) {
-                continue;
+
+            if !self.reduce_step(registry, strategies, trace, &mut max_level, max) {
+                break;
             }
-            break;
         }
 
         (max_level, false)
     }
 
-    pub fn reduce_level(&mut self, max_level: &mut Level, level: &Level, strategies: &mut Option<&mut HashSet<&'static str>>, fns: Vec<(fn(&mut Grid) -> bool, &'static str)>) -> bool {
-        for (f, n) in fns {
-            if f(self) {
-                if let Some(s) = strategies {
-                    s.insert(n);
-                }
-                if *max_level < *level {
-                    *max_level = *level;
+    // reduce_step applies the first strategy of the registry, up to the max level, that changes the grid. It adds its name to strategies and raises max_level to its level. It returns true if a strategy changes the grid.
+    fn reduce_step(&mut self, registry: &Registry, strategies: &mut Option<&mut HashSet<&'static str>>, trace: &mut Option<&mut Trace>, max_level: &mut Level, max: Level) -> bool {
+        for tier in registry.tiers() {
+            for strategy in tier.iter().filter(|s| s.level() <= max) {
+                let changed = match trace {
+                    Some(t) => t.record(self, strategy.as_ref(), registry),
+                    None => strategy.apply(self, registry),
+                };
+                if changed {
+                    if let Some(s) = strategies {
+                        s.insert(strategy.name());
+                    }
+                    if *max_level < strategy.level() {
+                        *max_level = strategy.level();
+                    }
+                    return true;
                 }
-                return true;
             }
         }
+
         false
     }
 
@@ -472,7 +460,7 @@ impl Grid {
         }
 
         if self.solved() {
-            solutions.push((*self).clone());
+            solutions.push(*self);
         }
 
         if self.empty_cell() {
@@ -488,9 +476,11 @@ impl Grid {
         digits.shuffle(&mut thread_rng());
 
         for d in digits {
-            let mut copy = self.clone();
+            // The search is counting solutions, so it cannot assume there is only one. It propagates each guess with only the easy and standard strategies, since it will guess again rather than use the slower tiers.
+            let mut copy = *self;
+            copy.assume_unique = false;
             copy[&point] = Cell(1 << d);
-            let (_, solved) = copy.reduce(&mut None);
+            let (_, solved) = copy.reduce_to(&REGISTRY, &mut None, &mut None, Level::Standard);
 
             if solved {
                 solutions.push(copy);
@@ -545,13 +535,13 @@ impl Grid {
 
 impl Index<&Point> for Grid {
     type Output = Cell;
-    fn index<'a>(&'a self, p: &Point) -> &Cell {
+    fn index(&self, p: &Point) -> &Cell {
         &self.cells[p.0][p.1]
     }
 }
 
 impl IndexMut<&Point> for Grid {
-    fn index_mut<'a>(&'a mut self, p: &Point) -> &mut Cell {
+    fn index_mut(&mut self, p: &Point) -> &mut Cell {
         &mut self.cells[p.0][p.1]
     }
 }
@@ -567,5 +557,18 @@ fn center(s: &str, width: usize) -> String {
 }
 
 fn count<T: Into<usize>>(cell: T) -> u8 {
-    BIT_COUNT[cell.into() as usize]
+    BIT_COUNT[cell.into()]
+}
+
+// marked builds a candidate grid for tests with parse_candidates: the listed cells have the given candidates and every other cell has all nine digits.
+#[cfg(test)]
+fn marked(marks: &[(Point, &str)]) -> Grid {
+    let mut s = String::new();
+    for_all_cells!(r, c, {
+        let candidates = marks.iter().find(|(p, _)| *p == (r, c)).map_or("123456789", |(_, m)| m);
+        for digit in ALL_DIGITS {
+            s.push(if candidates.contains(&digit.to_string()) { (b'0' + digit as u8) as char } else { '.' });
+        }
+    });
+    Grid::parse_candidates(&s).unwrap()
 }
@@ -0,0 +1,505 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Cell, Grid, Point, ALL, ALL_DIGITS, BOX, COL, COLS, MAX_CHAIN, ROW, ROWS};
+use std::error::Error;
+use std::fmt;
+
+// A parse error describes why an input is not a puzzle. Positions count characters from 0.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    // The input does not have exactly 81 cells.
+    Length(usize),
+    // The input has a character that is neither a given nor a blank (nor, for the tolerant parser, a separator).
+    Character { position: usize, found: char },
+    // A digit is given (or solved) more than once in a box, column or row.
+    Duplicate { digit: usize, group: String, unit: usize },
+    // A cell of a candidate grid has no candidates.
+    Empty(Point),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Length(len) => write!(f, "expected {} cells, found {}", ROWS * COLS, len),
+            ParseError::Character { position, found } => write!(f, "illegal character {:?} at position {}", found, position),
+            ParseError::Duplicate { digit, group, unit } => write!(f, "digit {} is given more than once in {} {}", digit, group, unit),
+            ParseError::Empty(p) => write!(f, "cell {:?} has no candidates", p),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl Grid {
+    // parse_grid parses a string of digits and dots into a game structure containing two matrices: the orig matrix contains a 'true' where that cell was set in the start-up puzzle and curr contains u16's where the bits are set if that digit is valid. Trailing whitespace, such as a carriage return, is ignored. It returns an error if the input is not 81 digits and dots or if a digit is given twice in a unit.
+    pub fn parse_grid(input: &str) -> Result<Grid, ParseError> {
+        let chars: Vec<char> = input.trim_end().chars().collect();
+        if chars.len() != ROWS * COLS {
+            return Err(ParseError::Length(chars.len()));
+        }
+
+        if let Some((position, &found)) = chars.iter().enumerate().find(|(_, chr)| !matches!(chr, '.' | '1'..='9')) {
+            return Err(ParseError::Character { position, found });
+        }
+
+        Grid::from_givens(&chars)
+    }
+
+    // parse_puzzle is a tolerant parse_grid. Blanks may be written as '.', '0', '_', '*' or '-', and whitespace, '|', '+', '=' and box-drawing characters are ignored, so the puzzle may be laid out on one line or as a 9x9 block with separator lines between the boxes.
+    pub fn parse_puzzle(input: &str) -> Result<Grid, ParseError> {
+        let mut chars = Vec::<char>::with_capacity(ROWS * COLS);
+        let mut position = 0;
+
+        for line in input.split_inclusive('\n') {
+            if !separator(line) {
+                for (i, chr) in line.chars().enumerate() {
+                    match chr {
+                        '1'..='9' => chars.push(chr),
+                        chr if blank(chr) => chars.push('.'),
+                        chr if frame(chr) => {}
+                        found => return Err(ParseError::Character { position: position + i, found }),
+                    }
+                }
+            }
+            position += line.chars().count();
+        }
+
+        if chars.len() != ROWS * COLS {
+            return Err(ParseError::Length(chars.len()));
+        }
+
+        Grid::from_givens(&chars)
+    }
+
+    // encode writes the givens of the grid in the one-line format read by parse_grid.
+    pub fn encode(&self) -> String {
+        let mut s = String::with_capacity(ROWS * COLS);
+        for_all_cells!(r, c, {
+            s.push(if self.orig[r][c] { (b'0' + self.cells[r][c].digits()[0] as u8) as char } else { '.' });
+        });
+        s
+    }
+
+    // from_givens builds a grid from 81 digits and dots, checking that no digit is given twice in a unit.
+    fn from_givens(chars: &[char]) -> Result<Grid, ParseError> {
+        let mut orig: [[bool; COLS]; ROWS] = Default::default();
+        let mut cells = [[Cell(ALL); COLS]; ROWS];
+        for_all_cells!(r, c, {
+            if let Some(digit) = chars[r * 9 + c].to_digit(10) {
+                orig[r][c] = true;
+                cells[r][c] = Cell(1 << digit);
+            }
+        });
+
+        let grid = Grid {
+            orig,
+            cells,
+            assume_unique: false,
+            max_chain: MAX_CHAIN,
+        };
+        grid.check_solved()?;
+        Ok(grid)
+    }
+
+    // check_solved returns an error if a digit is given or solved more than once in a box, column or row.
+    fn check_solved(&self) -> Result<(), ParseError> {
+        for group in &[&*BOX, &*COL, &*ROW] {
+            for (ui, u) in group.cells.iter().enumerate() {
+                let mut seen = Cell(0);
+                for p in u.iter().filter(|p| self[p].count() == 1) {
+                    if seen.0 & self[p].0 != 0 {
+                        return Err(ParseError::Duplicate {
+                            digit: self[p].digits()[0],
+                            group: group.name.clone(),
+                            unit: ui,
+                        });
+                    }
+                    seen = seen | self[p];
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // parse_candidates parses a grid of pencil marks, either the 729 character form written by encode_candidates or the boxed text grid written by format_candidates (as used by HoDoKu and SudokuWiki). No cell is marked as given, since neither form records the givens. It returns an error if there are not 81 cells, if a cell has no candidates or if a digit is solved twice in a unit.
+    pub fn parse_candidates(input: &str) -> Result<Grid, ParseError> {
+        let mut cells = [[Cell(0); COLS]; ROWS];
+        let compact = input.trim();
+
+        if compact.chars().count() == ROWS * COLS * 9 && !compact.contains(char::is_whitespace) {
+            // The 729 character form: the digit, or a dot or 0 if it is not a candidate, for each digit of each cell.
+            for (i, chr) in compact.chars().enumerate() {
+                match chr {
+                    '.' | '0' => {}
+                    '1'..='9' if chr.to_digit(10) == Some(i as u32 % 9 + 1) => cells[i / 81][i / 9 % 9].0 |= 1 << (i % 9 + 1),
+                    found => return Err(ParseError::Character { position: i, found }),
+                }
+            }
+        } else {
+            // The boxed text grid: the candidates of each cell as a run of digits, with any of ".:'-+|*=" and box-drawing characters as borders.
+            let mut count = 0;
+            let mut cell = Cell(0);
+            for (position, chr) in input.chars().chain(std::iter::once(' ')).enumerate() {
+                if let Some(digit) = chr.to_digit(10).filter(|&d| d > 0) {
+                    cell.0 |= 1 << digit;
+                    continue;
+                }
+                if !frame(chr) && !matches!(chr, '.' | ':' | '\'' | '-' | '*') {
+                    return Err(ParseError::Character { position, found: chr });
+                }
+                if cell.0 != 0 {
+                    if count < ROWS * COLS {
+                        cells[count / 9][count % 9] = cell;
+                    }
+                    count += 1;
+                    cell = Cell(0);
+                }
+            }
+            if count != ROWS * COLS {
+                return Err(ParseError::Length(count));
+            }
+        }
+
+        for_all_cells!(r, c, {
+            if cells[r][c].count() == 0 {
+                return Err(ParseError::Empty((r, c)));
+            }
+        });
+
+        let grid = Grid {
+            orig: [[false; COLS]; ROWS],
+            cells,
+            assume_unique: false,
+            max_chain: MAX_CHAIN,
+        };
+        grid.check_solved()?;
+        Ok(grid)
+    }
+
+    // encode_candidates writes the candidates of the grid as 729 characters, the digit or a dot for each digit of each cell.
+    pub fn encode_candidates(&self) -> String {
+        let mut s = String::with_capacity(ROWS * COLS * 9);
+        for_all_cells!(r, c, {
+            for digit in ALL_DIGITS {
+                s.push(if self.cells[r][c].0 & (1 << digit) != 0 { (b'0' + digit as u8) as char } else { '.' });
+            }
+        });
+        s
+    }
+
+    // format_candidates writes the candidates of the grid as a boxed text grid in the layout used by HoDoKu, with each column as wide as its widest cell.
+    pub fn format_candidates(&self) -> String {
+        let marks: Vec<Vec<String>> = self
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.digits().iter().map(|d| d.to_string()).collect()).collect())
+            .collect();
+        let widths: Vec<usize> = (0..COLS).map(|c| marks.iter().map(|row| row[c].len()).max().unwrap_or(1)).collect();
+        let section = |b: usize| widths[b * 3..b * 3 + 3].iter().sum::<usize>() + 6;
+        let border = |left: char, middle: char, right: char| {
+            let bars: Vec<String> = (0..3).map(|b| "-".repeat(section(b))).collect();
+            format!("{}{}{}{}{}{}{}\n", left, bars[0], middle, bars[1], middle, bars[2], right)
+        };
+
+        let mut s = border('.', '.', '.');
+        for (r, row) in marks.iter().enumerate() {
+            if r == 3 || r == 6 {
+                s += &border(':', '+', ':');
+            }
+            for (c, cell) in row.iter().enumerate() {
+                if c % 3 == 0 {
+                    s += "| ";
+                }
+                s += &format!("{:<width$}", cell, width = widths[c]);
+                s += if c % 3 == 2 { " " } else { "  " };
+            }
+            s += "|\n";
+        }
+        s + &border('\'', '\'', '\'')
+    }
+}
+
+// parse_lines parses a file of one puzzle per line with the strict parse_grid. It returns the line number (counting from 1) of each puzzle with the result.
+pub fn parse_lines(input: &str) -> Vec<(usize, Result<Grid, ParseError>)> {
+    input.lines().enumerate().map(|(n, line)| (n + 1, Grid::parse_grid(line))).collect()
+}
+
+// one_per_line checks for a file of one puzzle per line, such as those in test_puzzles: every line has 81 characters, ignoring trailing whitespace. Such files are read strictly with parse_lines, and any other layout with parse_puzzles.
+pub fn one_per_line(input: &str) -> bool {
+    input.lines().next().is_some() && input.lines().all(|line| line.trim_end().chars().count() == ROWS * COLS)
+}
+
+// parse_puzzles splits the contents of a puzzle file into puzzles and parses each of them with parse_puzzle. It returns the line number (counting from 1) of the start of each puzzle with the result. A puzzle is either a line of 81 cells or a block of lines of 9 cells, so one-line collections, 9x9 layouts and .sdk and .ss files are read. Empty lines, comments starting with '#' and sections other than [Puzzle] are skipped.
+pub fn parse_puzzles(input: &str) -> Vec<(usize, Result<Grid, ParseError>)> {
+    let mut puzzles = Vec::new();
+    let mut block: Option<(usize, String, usize)> = None;
+    let mut skipping = false;
+
+    let mut flush = |block: &mut Option<(usize, String, usize)>| {
+        if let Some((n, text, _)) = block.take() {
+            puzzles.push((n, Grid::parse_puzzle(&text)));
+        }
+    };
+
+    for (n, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            flush(&mut block);
+            continue;
+        }
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            flush(&mut block);
+            skipping = !trimmed.eq_ignore_ascii_case("[puzzle]");
+            continue;
+        }
+        if skipping || separator(line) {
+            continue;
+        }
+
+        let cells = line.chars().filter(|&chr| !frame(chr)).count();
+        match block.as_mut() {
+            Some((_, text, count)) => {
+                text.push('\n');
+                text.push_str(line);
+                *count += cells;
+            }
+            None if cells == COLS => block = Some((n + 1, line.to_string(), cells)),
+            None => {
+                block = Some((n + 1, line.to_string(), cells));
+                flush(&mut block);
+            }
+        }
+        if block.as_ref().is_some_and(|b| b.2 >= ROWS * COLS) {
+            flush(&mut block);
+        }
+    }
+    flush(&mut block);
+
+    puzzles
+}
+
+// blank checks for the characters used for empty cells.
+fn blank(chr: char) -> bool {
+    matches!(chr, '.' | '0' | '_' | '*' | '-')
+}
+
+// frame checks for the characters ignored between cells: whitespace, '|', '+', '=' and box-drawing characters.
+fn frame(chr: char) -> bool {
+    chr.is_whitespace() || matches!(chr, '|' | '+' | '=' | '\u{2500}'..='\u{257f}')
+}
+
+// separator checks for a line drawn between boxes, such as "---+---+---" or "*-----------*". Since '-' and '*' can also be blanks, a line of exactly 9 of them with only vertical bars between them is a row of empty cells.
+fn separator(line: &str) -> bool {
+    if !line.chars().all(|chr| frame(chr) || chr == '-' || chr == '*') {
+        return false;
+    }
+
+    let vertical = |chr: char| chr.is_whitespace() || matches!(chr, '|' | '\u{2502}' | '\u{2503}' | '\u{2551}');
+    let dashes = line.chars().filter(|&chr| chr == '-' || chr == '*').count();
+    line.chars().any(|chr| frame(chr) && !vertical(chr)) || (dashes != COLS && dashes != ROWS * COLS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+    // with replaces the character at position i of the puzzle.
+    fn with(i: usize, chr: char) -> String {
+        PUZZLE.chars().enumerate().map(|(j, c)| if j == i { chr } else { c }).collect()
+    }
+
+    #[test]
+    fn parse_grid_encodes_givens() {
+        let grid = Grid::parse_grid(PUZZLE).unwrap();
+        assert_eq!(grid.encode(), PUZZLE);
+        assert!(grid.orig[0][2] && !grid.orig[0][0]);
+        assert_eq!(grid.cells[0][2], Cell(1 << 3));
+        assert_eq!(grid.cells[0][0], Cell(ALL));
+    }
+
+    #[test]
+    fn parse_grid_ignores_trailing_whitespace() {
+        assert_eq!(Grid::parse_grid(&format!("{}\r\n", PUZZLE)).unwrap().encode(), PUZZLE);
+    }
+
+    #[test]
+    fn parse_grid_length() {
+        assert_eq!(Grid::parse_grid(&PUZZLE[..80]).err(), Some(ParseError::Length(80)));
+        assert_eq!(Grid::parse_grid(&format!("{}.", PUZZLE)).err(), Some(ParseError::Length(82)));
+    }
+
+    #[test]
+    fn parse_grid_character() {
+        assert_eq!(Grid::parse_grid(&with(4, '0')).err(), Some(ParseError::Character { position: 4, found: '0' }));
+        assert_eq!(Grid::parse_grid(&with(80, 'x')).err(), Some(ParseError::Character { position: 80, found: 'x' }));
+    }
+
+    #[test]
+    fn parse_grid_duplicate() {
+        // A 3 in the corner repeats the 3 given in box 0 (and row 0).
+        let err = ParseError::Duplicate {
+            digit: 3,
+            group: "box".to_string(),
+            unit: 0,
+        };
+        assert_eq!(Grid::parse_grid(&with(0, '3')).err(), Some(err));
+
+        // A 1 in the last cell repeats the 1s given in column 8 and row 8. Boxes are checked first, then columns, then rows.
+        let err = ParseError::Duplicate {
+            digit: 1,
+            group: "col".to_string(),
+            unit: 8,
+        };
+        assert_eq!(Grid::parse_grid(&with(80, '1')).err(), Some(err));
+    }
+
+    // rows splits the puzzle into its rows, with each box of a row joined by sep.
+    fn rows(sep: &str) -> Vec<String> {
+        let chars: Vec<char> = PUZZLE.chars().collect();
+        chars
+            .chunks(9)
+            .map(|row| row.chunks(3).map(|b| b.iter().collect::<String>()).collect::<Vec<String>>().join(sep))
+            .collect()
+    }
+
+    // encoded returns the line numbers and encoded givens of the puzzles, or the errors.
+    fn encoded(input: &str) -> Vec<(usize, Result<String, ParseError>)> {
+        parse_puzzles(input).into_iter().map(|(n, res)| (n, res.map(|grid| grid.encode()))).collect()
+    }
+
+    #[test]
+    fn parse_puzzle_blanks() {
+        for blank in ['0', '_', '*', '-'] {
+            assert_eq!(Grid::parse_puzzle(&PUZZLE.replace('.', &blank.to_string())).unwrap().encode(), PUZZLE);
+        }
+        assert_eq!(Grid::parse_puzzle(&with(3, 'x')).err(), Some(ParseError::Character { position: 3, found: 'x' }));
+        assert_eq!(Grid::parse_puzzle(&PUZZLE[1..]).err(), Some(ParseError::Length(80)));
+    }
+
+    #[test]
+    fn parse_puzzles_lines_and_comments() {
+        let input = format!("# first\n{}\n\n# second\n{}\n", PUZZLE, PUZZLE.replace('.', "0"));
+        assert_eq!(encoded(&input), vec![(2, Ok(PUZZLE.to_string())), (5, Ok(PUZZLE.to_string()))]);
+    }
+
+    #[test]
+    fn parse_puzzles_reports_bad_puzzles() {
+        let input = format!("{}\n{}\n{}\n", &PUZZLE[1..], with(0, '3'), PUZZLE);
+        let puzzles = encoded(&input);
+        assert_eq!(puzzles.len(), 3);
+        assert_eq!(puzzles[0], (1, Err(ParseError::Length(80))));
+        assert!(matches!(puzzles[1], (2, Err(ParseError::Duplicate { .. }))));
+        assert_eq!(puzzles[2], (3, Ok(PUZZLE.to_string())));
+    }
+
+    #[test]
+    fn parse_puzzles_9x9() {
+        let input = format!("{}\n\n{}\n", rows("").join("\n"), rows("").join("\n"));
+        assert_eq!(encoded(&input), vec![(1, Ok(PUZZLE.to_string())), (11, Ok(PUZZLE.to_string()))]);
+    }
+
+    #[test]
+    fn parse_puzzles_ss() {
+        let rows = rows("|");
+        let input = format!("{}\n-----------\n{}\n-----------\n{}\n", rows[..3].join("\n"), rows[3..6].join("\n"), rows[6..].join("\n"));
+        assert_eq!(encoded(&input), vec![(1, Ok(PUZZLE.to_string()))]);
+    }
+
+    #[test]
+    fn parse_puzzles_boxed() {
+        let rows: Vec<String> = rows(" | ").iter().map(|row| format!("| {} |", row)).collect();
+        let input = format!(
+            "*-----------*\n{}\n|---+---+---|\n{}\n|---+---+---|\n{}\n*-----------*\n",
+            rows[..3].join("\n"),
+            rows[3..6].join("\n"),
+            rows[6..].join("\n")
+        );
+        assert_eq!(encoded(&input), vec![(2, Ok(PUZZLE.to_string()))]);
+    }
+
+    #[test]
+    fn parse_puzzles_sections() {
+        let input = format!("[Puzzle]\n{}\n\n[State]\n{}\n[puzzle]\n{}\n", rows("").join("\n"), with(0, '3'), PUZZLE);
+        assert_eq!(encoded(&input), vec![(2, Ok(PUZZLE.to_string())), (15, Ok(PUZZLE.to_string()))]);
+    }
+
+    // reduced returns the puzzle with the naked singles removed, so that cells have differing numbers of candidates.
+    fn reduced() -> Grid {
+        let mut grid = Grid::parse_grid(PUZZLE).unwrap();
+        grid.naked_single();
+        grid
+    }
+
+    // marks builds a 729 character candidate grid where every cell has all digits except those given.
+    fn marks(given: &[(usize, &str)]) -> String {
+        (0..ROWS * COLS).map(|i| given.iter().find(|(j, _)| *j == i).map_or("123456789", |(_, m)| m)).collect()
+    }
+
+    #[test]
+    fn candidates_round_trip() {
+        let grid = reduced();
+        let encoded = grid.encode_candidates();
+        assert_eq!(encoded.len(), ROWS * COLS * 9);
+        assert_eq!(Grid::parse_candidates(&encoded).unwrap().cells, grid.cells);
+        assert_eq!(Grid::parse_candidates(&encoded.replace('.', "0")).unwrap().cells, grid.cells);
+    }
+
+    #[test]
+    fn boxed_candidates_round_trip() {
+        let grid = reduced();
+        let boxed = Grid::parse_candidates(&grid.format_candidates()).unwrap();
+        assert_eq!(boxed.cells, grid.cells);
+        assert_eq!(boxed.encode_candidates(), grid.encode_candidates());
+        assert_eq!(boxed.format_candidates(), grid.format_candidates());
+        assert!(boxed.orig.iter().flatten().all(|given| !given));
+    }
+
+    #[test]
+    fn parse_candidates_errors() {
+        assert_eq!(Grid::parse_candidates(&marks(&[(0, ".........")])).err(), Some(ParseError::Empty((0, 0))));
+        assert_eq!(Grid::parse_candidates(&marks(&[(2, "2........")])).err(), Some(ParseError::Character { position: 18, found: '2' }));
+        assert_eq!(Grid::parse_candidates("12 3 | 45").err(), Some(ParseError::Length(3)));
+        assert_eq!(Grid::parse_candidates("12 x").err(), Some(ParseError::Character { position: 3, found: 'x' }));
+
+        let err = ParseError::Duplicate {
+            digit: 5,
+            group: "row".to_string(),
+            unit: 4,
+        };
+        assert_eq!(Grid::parse_candidates(&marks(&[(36, "....5...."), (44, "....5....")])).err(), Some(err));
+    }
+
+    #[test]
+    fn one_per_line_files() {
+        let lines = format!("{}\n{}\r\n", PUZZLE, with(0, '0'));
+        assert!(one_per_line(&lines));
+        assert!(!one_per_line(""));
+        assert!(!one_per_line(&format!("# comment\n{}\n", PUZZLE)));
+        assert!(!one_per_line(&format!("{}\n\n{}\n", PUZZLE, PUZZLE)));
+        assert!(!one_per_line(&rows("").join("\n")));
+
+        // parse_lines is strict: a 0 is not a blank.
+        let puzzles = parse_lines(&lines);
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].0, 1);
+        assert_eq!(puzzles[0].1.as_ref().map(|grid| grid.encode()).ok(), Some(PUZZLE.to_string()));
+        assert_eq!(puzzles[1].1.as_ref().err(), Some(&ParseError::Character { position: 0, found: '0' }));
+    }
+}
@@ -0,0 +1,328 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::Level;
+use super::Grid;
+
+type Tier = Vec<Box<dyn Strategy>>;
+
+// A built-in strategy is a Grid method and its name.
+pub(super) type Builtin = (fn(&mut Grid) -> bool, &'static str);
+
+// A strategy is one logical technique for removing candidates from a grid. The level is the difficulty of the technique, used to rate puzzles.
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn level(&self) -> Level;
+    // apply tries the strategy once and returns true if it changes any cells. The registry is the one reducing the grid, for strategies that reduce copies of the grid.
+    fn apply(&self, grid: &mut Grid, registry: &Registry) -> bool;
+}
+
+// FnStrategy is a strategy implemented by a function, such as the built-in Grid methods.
+pub struct FnStrategy {
+    name: &'static str,
+    level: Level,
+    f: fn(&mut Grid) -> bool,
+}
+
+impl FnStrategy {
+    pub fn new(name: &'static str, level: Level, f: fn(&mut Grid) -> bool) -> FnStrategy {
+        FnStrategy { name, level, f }
+    }
+}
+
+impl Strategy for FnStrategy {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn level(&self) -> Level {
+        self.level
+    }
+
+    fn apply(&self, grid: &mut Grid, _registry: &Registry) -> bool {
+        (self.f)(grid)
+    }
+}
+
+// ForcingStrategy is a forcing strategy implemented by a function. It tries candidates on copies of the grid and reduces them with the strategies of the registry up to the standard level.
+pub struct ForcingStrategy {
+    name: &'static str,
+    f: fn(&mut Grid, &Registry) -> bool,
+}
+
+impl ForcingStrategy {
+    pub fn new(name: &'static str, f: fn(&mut Grid, &Registry) -> bool) -> ForcingStrategy {
+        ForcingStrategy { name, f }
+    }
+}
+
+impl Strategy for ForcingStrategy {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn level(&self) -> Level {
+        Level::Forcing
+    }
+
+    fn apply(&self, grid: &mut Grid, registry: &Registry) -> bool {
+        (self.f)(grid, registry)
+    }
+}
+
+// A registry holds the strategies used by Grid::reduce_with, in tiers. The strategies are tried in order, tier by tier, and after any of them changes the grid the reduction starts again with the first strategy of the first tier.
+pub struct Registry {
+    tiers: Vec<Tier>,
+}
+
+impl Registry {
+    // new creates a registry with no strategies.
+    pub fn new() -> Registry {
+        Registry { tiers: Vec::new() }
+    }
+
+    // with_forcing creates a registry with the built-in strategies and a last tier of the forcing strategies (cell and unit forcing chains and nishio), which try candidates on copies of the grid.
+    pub fn with_forcing() -> Registry {
+        let mut registry = Registry::default();
+        registry.tiers.push(vec![
+            Box::new(ForcingStrategy::new("cell_forcing_chain", Grid::cell_forcing_chain)),
+            Box::new(ForcingStrategy::new("unit_forcing_chain", Grid::unit_forcing_chain)),
+            Box::new(ForcingStrategy::new("nishio", Grid::nishio)),
+        ]);
+        registry
+    }
+
+    pub fn tiers(&self) -> &[Tier] {
+        &self.tiers
+    }
+
+    // add_tier inserts an empty tier at the index and returns the index.
+    pub fn add_tier(&mut self, index: usize) -> usize {
+        self.tiers.insert(index, Vec::new());
+        index
+    }
+
+    // add appends a strategy to a tier, adding tiers at the end if needed.
+    pub fn add(&mut self, tier: usize, strategy: Box<dyn Strategy>) {
+        while self.tiers.len() <= tier {
+            self.tiers.push(Vec::new());
+        }
+        self.tiers[tier].push(strategy);
+    }
+
+    // insert inserts a strategy into a tier at the index, adding tiers at the end if needed.
+    pub fn insert(&mut self, tier: usize, index: usize, strategy: Box<dyn Strategy>) {
+        while self.tiers.len() <= tier {
+            self.tiers.push(Vec::new());
+        }
+        self.tiers[tier].insert(index, strategy);
+    }
+
+    // find returns the tier and index of the named strategy.
+    pub fn find(&self, name: &str) -> Option<(usize, usize)> {
+        for (ti, tier) in self.tiers.iter().enumerate() {
+            if let Some(si) = tier.iter().position(|s| s.name() == name) {
+                return Some((ti, si));
+            }
+        }
+        None
+    }
+
+    // remove removes the named strategy and returns it.
+    pub fn remove(&mut self, name: &str) -> Option<Box<dyn Strategy>> {
+        let (ti, si) = self.find(name)?;
+        Some(self.tiers[ti].remove(si))
+    }
+
+    // move_to moves the named strategy to a tier and index. It returns false if there is no such strategy.
+    pub fn move_to(&mut self, name: &str, tier: usize, index: usize) -> bool {
+        match self.remove(name) {
+            Some(strategy) => {
+                self.insert(tier, index, strategy);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for Registry {
+    // default creates a registry with the built-in strategies, one tier per level, without the forcing strategies.
+    fn default() -> Registry {
+        let tier = |level: Level, fns: &[Builtin]| -> Tier { fns.iter().map(|&(f, name)| Box::new(FnStrategy::new(name, level, f)) as Box<dyn Strategy>).collect() };
+
+        Registry {
+            tiers: vec![
+                tier(
+                    Level::Easy,
+                    &[
+                        (Grid::naked_single, "naked_single"),
+                        (Grid::hidden_single, "hidden_single"),
+                        (Grid::naked_pair, "naked_pair"),
+                        (Grid::naked_triple, "naked_triple"),
+                        (Grid::naked_quad, "naked_quad"),
+                        (Grid::hidden_pair, "hidden_pair"),
+                        (Grid::hidden_triple, "hidden_triple"),
+                        (Grid::hidden_quad, "hidden_quad"),
+                        (Grid::pointing_line, "pointing_line"),
+                        (Grid::box_line, "box_line"),
+                    ],
+                ),
+                tier(
+                    Level::Standard,
+                    &[
+                        (Grid::x_wing, "x_wing"),
+                        (Grid::skyscraper, "skyscraper"),
+                        (Grid::two_string_kite, "two_string_kite"),
+                        (Grid::y_wing, "y_wing"),
+                    ],
+                ),
+                tier(
+                    Level::Hard,
+                    &[
+                        (Grid::swordfish, "swordfish"),
+                        (Grid::jellyfish, "jellyfish"),
+                        (Grid::finned_x_wing, "finned_x_wing"),
+                        (Grid::sashimi_x_wing, "sashimi_x_wing"),
+                        (Grid::empty_rectangle, "empty_rectangle"),
+                        (Grid::xyz_wing, "xyz_wing"),
+                        (Grid::singles_chain, "singles_chain"),
+                        (Grid::unique_rectangle_1, "unique_rectangle_1"),
+                        (Grid::unique_rectangle_2, "unique_rectangle_2"),
+                        (Grid::unique_rectangle_4, "unique_rectangle_4"),
+                        (Grid::bug_1, "bug_1"),
+                    ],
+                ),
+                tier(
+                    Level::Expert,
+                    &[
+                        (Grid::finned_swordfish, "finned_swordfish"),
+                        (Grid::sashimi_swordfish, "sashimi_swordfish"),
+                        (Grid::finned_jellyfish, "finned_jellyfish"),
+                        (Grid::sashimi_jellyfish, "sashimi_jellyfish"),
+                        (Grid::wxyz_wing, "wxyz_wing"),
+                        (Grid::sue_de_coq, "sue_de_coq"),
+                        (Grid::unique_rectangle_3, "unique_rectangle_3"),
+                        (Grid::unique_rectangle_5, "unique_rectangle_5"),
+                        (Grid::unique_rectangle_6, "unique_rectangle_6"),
+                        (Grid::hidden_unique_rectangle, "hidden_unique_rectangle"),
+                        (Grid::bug_n, "bug_n"),
+                        (Grid::x_cycles, "x_cycles"),
+                        (Grid::xy_chain, "xy_chain"),
+                        (Grid::medusa, "medusa"),
+                    ],
+                ),
+                tier(
+                    Level::Extreme,
+                    &[
+                        (Grid::sk_loop, "sk_loop"),
+                        (Grid::als_xz, "als_xz"),
+                        (Grid::als_xy_wing, "als_xy_wing"),
+                        (Grid::death_blossom, "death_blossom"),
+                        (Grid::aic, "aic"),
+                    ],
+                ),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const PUZZLE: &str = "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+
+    // names lists the names of the strategies in each tier.
+    fn names(registry: &Registry) -> Vec<Vec<&'static str>> {
+        registry.tiers().iter().map(|tier| tier.iter().map(|s| s.name()).collect()).collect()
+    }
+
+    fn strategy(name: &'static str) -> Box<dyn Strategy> {
+        Box::new(FnStrategy::new(name, Level::Easy, Grid::naked_single))
+    }
+
+    #[test]
+    fn add_and_insert() {
+        let mut registry = Registry::new();
+        registry.add(1, strategy("b"));
+        registry.add(1, strategy("d"));
+        registry.insert(1, 1, strategy("c"));
+        registry.insert(0, 0, strategy("a"));
+        assert_eq!(names(&registry), vec![vec!["a"], vec!["b", "c", "d"]]);
+
+        assert_eq!(registry.add_tier(1), 1);
+        registry.add(3, strategy("e"));
+        assert_eq!(names(&registry), vec![vec!["a"], vec![], vec!["b", "c", "d"], vec!["e"]]);
+        assert_eq!(registry.find("d"), Some((2, 2)));
+        assert_eq!(registry.find("f"), None);
+    }
+
+    #[test]
+    fn remove_and_move_to() {
+        let mut registry = Registry::new();
+        for name in ["a", "b", "c"] {
+            registry.add(0, strategy(name));
+        }
+        registry.add(1, strategy("d"));
+
+        assert_eq!(registry.remove("b").map(|s| s.name()), Some("b"));
+        assert!(registry.remove("b").is_none());
+        assert_eq!(names(&registry), vec![vec!["a", "c"], vec!["d"]]);
+
+        assert!(registry.move_to("a", 1, 1));
+        assert_eq!(names(&registry), vec![vec!["c"], vec!["d", "a"]]);
+        assert!(registry.move_to("d", 0, 0));
+        assert_eq!(names(&registry), vec![vec!["d", "c"], vec!["a"]]);
+        assert!(!registry.move_to("b", 0, 0));
+        assert_eq!(names(&registry), vec![vec!["d", "c"], vec!["a"]]);
+    }
+
+    #[test]
+    fn forcing_tier() {
+        let default = Registry::default();
+        let forcing = Registry::with_forcing();
+        assert_eq!(forcing.tiers().len(), default.tiers().len() + 1);
+        assert_eq!(names(&forcing).last().unwrap(), &vec!["cell_forcing_chain", "unit_forcing_chain", "nishio"]);
+        assert!(forcing.tiers().last().unwrap().iter().all(|s| s.level() == Level::Forcing));
+        assert!(default.find("nishio").is_none());
+        assert_eq!(forcing.find("naked_single"), default.find("naked_single"));
+    }
+
+    #[test]
+    fn reduce_with_custom_registry() {
+        // The singles alone solve the puzzle. naked_single is registered under another name in a later tier at the standard level, which shows in the names and the level returned.
+        let mut registry = Registry::new();
+        registry.add(0, Box::new(FnStrategy::new("hidden_single", Level::Easy, Grid::hidden_single)));
+        registry.add(1, Box::new(FnStrategy::new("custom_single", Level::Standard, Grid::naked_single)));
+
+        let mut grid = Grid::parse_grid(PUZZLE).unwrap();
+        let mut used = HashSet::new();
+        assert_eq!(grid.reduce_with(&registry, &mut Some(&mut used), &mut None), (Level::Standard, true));
+        assert!(used.contains("custom_single"));
+        assert!(!used.contains("naked_single"));
+        assert_eq!(grid.encode_candidates().chars().filter(|&chr| chr != '.').count(), 81);
+    }
+
+    #[test]
+    fn reduce_with_empty_registry() {
+        let mut grid = Grid::parse_grid(PUZZLE).unwrap();
+        let before = grid.encode_candidates();
+        assert_eq!(grid.reduce_with(&Registry::new(), &mut None, &mut None), (Level::Easy, false));
+        assert_eq!(grid.encode_candidates(), before);
+    }
+}
@@ -0,0 +1,217 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::Level;
+use super::registry::Strategy;
+use super::{Cell, Grid, Point, Registry, COLS, REGISTRY, ROWS};
+use std::cell::RefCell;
+use std::fmt;
+
+thread_local! {
+    // NOTES collects the cell_change! notes while a deduction is being recorded.
+    static NOTES: RefCell<Option<Vec<Note>>> = const { RefCell::new(None) };
+}
+
+// A note is one cell_change! made while deductions are being recorded: its message, the pattern cells and digits the strategy reported, and the grid after the change.
+pub(super) struct Note {
+    reason: String,
+    pattern: Vec<Point>,
+    digits: Cell,
+    grid: Grid,
+}
+
+// A deduction is one pattern found by a strategy: the cells and digits of the pattern, the strategy's messages describing it and the changes it makes.
+#[derive(Clone, Debug)]
+pub struct Deduction {
+    pub strategy: &'static str,
+    pub level: Level,
+    pub pattern: Vec<Point>,
+    pub digits: Vec<usize>,
+    pub reasons: Vec<String>,
+    // placements are the cells solved by the deduction.
+    pub placements: Vec<(Point, usize)>,
+    // eliminations are the candidates removed by the deduction, including those removed from solved cells.
+    pub eliminations: Vec<(Point, usize)>,
+}
+
+impl Deduction {
+    // new builds a deduction from the notes of one pattern, by comparing the grid before and after the pattern was applied.
+    fn new(strategy: &dyn Strategy, notes: &[Note], before: &Grid, after: &Grid) -> Deduction {
+        let mut placements = Vec::new();
+        let mut eliminations = Vec::new();
+
+        for_all_cells!(r, c, {
+            let (old, new) = (before.cells[r][c], after.cells[r][c]);
+            if old.count() > 1 && new.count() == 1 {
+                placements.push(((r, c), new.digits()[0]));
+            }
+            for d in (old & !new).digits() {
+                eliminations.push(((r, c), d));
+            }
+        });
+
+        let (pattern, digits) = match notes.first() {
+            Some(note) => (note.pattern.clone(), note.digits.digits()),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        Deduction {
+            strategy: strategy.name(),
+            level: strategy.level(),
+            pattern,
+            digits,
+            reasons: notes.iter().map(|n| n.reason.clone()).collect(),
+            placements,
+            eliminations,
+        }
+    }
+}
+
+impl Grid {
+    // next_step finds the next deduction the built-in strategies would make, without changing the grid. It returns None if the grid is solved, broken, or beyond the strategies.
+    pub fn next_step(&self) -> Option<Deduction> {
+        self.next_step_with(&REGISTRY)
+    }
+
+    // next_step_with is next_step using the strategies of a registry. It is the first pattern found by the first strategy that changes the grid.
+    pub fn next_step_with(&self, registry: &Registry) -> Option<Deduction> {
+        if self.empty_cell() || self.solved() {
+            return None;
+        }
+
+        for tier in registry.tiers() {
+            for strategy in tier {
+                let mut copy = *self;
+                let (changed, notes) = record_notes(|| strategy.apply(&mut copy, registry));
+                if changed {
+                    return deductions(strategy.as_ref(), notes, self, &copy).into_iter().next().map(|(deduction, _)| deduction);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// deductions splits the notes made by one application of a strategy into its patterns, a run of notes with the same pattern cells and digits, and returns the deduction for each pattern with the grid after it. Changes the strategy made without a note are given to the last pattern.
+pub(super) fn deductions(strategy: &dyn Strategy, notes: Vec<Note>, before: &Grid, after: &Grid) -> Vec<(Deduction, Grid)> {
+    let mut res = Vec::new();
+    let mut grid = *before;
+    let mut start = 0;
+
+    for end in 1..=notes.len() {
+        if end < notes.len() && notes[end].pattern == notes[start].pattern && notes[end].digits == notes[start].digits {
+            continue;
+        }
+
+        let next = if end == notes.len() { *after } else { notes[end - 1].grid };
+        res.push((Deduction::new(strategy, &notes[start..end], &grid, &next), next));
+        grid = next;
+        start = end;
+    }
+
+    if res.is_empty() {
+        res.push((Deduction::new(strategy, &[], before, after), *after));
+    }
+
+    res
+}
+
+// note adds a cell_change! to the deductions being recorded, if any.
+pub(super) fn note(grid: &Grid, pattern: &[Point], digits: Cell, args: fmt::Arguments) {
+    NOTES.with(|notes| {
+        if let Some(notes) = notes.borrow_mut().as_mut() {
+            notes.push(Note {
+                reason: args.to_string(),
+                pattern: pattern.to_vec(),
+                digits,
+                grid: *grid,
+            });
+        }
+    });
+}
+
+// record_notes runs f, collecting the cell_change! notes it makes.
+pub(super) fn record_notes<T>(f: impl FnOnce() -> T) -> (T, Vec<Note>) {
+    let saved = NOTES.with(|notes| notes.replace(Some(Vec::new())));
+    let res = f();
+    let recorded = NOTES.with(|notes| notes.replace(saved));
+    (res, recorded.unwrap_or_default())
+}
+
+// without_notes runs f without recording its cell_change! notes. Strategies that reduce copies of the grid use it so that the notes from the copies are not taken as their own.
+pub(super) fn without_notes<T>(f: impl FnOnce() -> T) -> T {
+    let saved = NOTES.with(|notes| notes.replace(None));
+    let res = f();
+    NOTES.with(|notes| notes.replace(saved));
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::marked;
+    use super::*;
+
+    #[test]
+    fn next_step_is_one_pattern() {
+        // Both solved cells give a naked single in their boxes, which naked_single clears in one pass. Only the first is the next step.
+        let grid = marked(&[((0, 0), "5"), ((8, 8), "6")]);
+        let before = grid.encode_candidates();
+        let step = grid.next_step().unwrap();
+
+        assert_eq!(step.strategy, "naked_single");
+        assert_eq!(step.level, Level::Easy);
+        assert_eq!(step.pattern, vec![(0, 0)]);
+        assert_eq!(step.digits, vec![5]);
+        assert!(step.placements.is_empty());
+        assert_eq!(step.eliminations.len(), 8);
+        assert!(step.eliminations.iter().all(|&((r, c), d)| d == 5 && r < 3 && c < 3));
+        assert_eq!(grid.encode_candidates(), before);
+    }
+
+    #[test]
+    fn next_step_placement() {
+        // The only 1 in row 0 is in (0, 0).
+        let marks: Vec<(Point, &str)> = (1..9).map(|c| ((0, c), "23456789")).collect();
+        let grid = marked(&marks);
+        let before = grid.encode_candidates();
+        let step = grid.next_step().unwrap();
+
+        assert_eq!(step.strategy, "hidden_single");
+        assert_eq!(step.pattern, vec![(0, 0)]);
+        assert_eq!(step.digits, vec![1]);
+        assert_eq!(step.placements, vec![((0, 0), 1)]);
+        assert_eq!(step.eliminations, (2..=9).map(|d| ((0, 0), d)).collect::<Vec<_>>());
+        assert_eq!(step.reasons.len(), 1);
+        assert_eq!(grid.encode_candidates(), before);
+    }
+
+    #[test]
+    fn next_step_with_registry() {
+        let grid = marked(&[((0, 0), "5")]);
+        assert!(grid.next_step_with(&Registry::new()).is_none());
+    }
+
+    #[test]
+    fn next_step_solved() {
+        let mut marks = Vec::new();
+        for_all_cells!(r, c, {
+            marks.push(((r, c), ((r * 3 + r / 3 + c) % 9 + 1).to_string()));
+        });
+        let marks: Vec<(Point, &str)> = marks.iter().map(|(p, m)| (*p, m.as_str())).collect();
+        assert!(marked(&marks).next_step().is_none());
+    }
+}
@@ -0,0 +1,379 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, Point, ALL_DIGITS, BOX, COL, COLS, ROW, ROWS, VISIBLE};
+use super::{bit, mask, points};
+use log::Level;
+use log::{info, log_enabled};
+use std::collections::VecDeque;
+
+// A node is a digit in one cell, or in a group of 2 or 3 cells in the intersection of a box and a row or column. Cells are stored as a bit mask with bit r * 9 + c set for cell (r, c).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Node {
+    pub digit: usize,
+    pub cells: u128,
+}
+
+// Rules select the nodes and links that a link graph is built from.
+#[derive(Clone, Copy)]
+pub(super) struct Rules {
+    // digit restricts the graph to a single digit.
+    pub digit: Option<usize>,
+    // bi_location adds strong links between the only two places for a digit in a unit.
+    pub bi_location: bool,
+    // bi_value adds strong links between the two digits of a cell with two candidates.
+    pub bi_value: bool,
+    // grouped adds nodes for the places of a digit in the intersection of a box and a row or column.
+    pub grouped: bool,
+    // cell_weak adds weak links between the digits of a cell.
+    pub cell_weak: bool,
+}
+
+// A link graph holds the nodes and the strong (if one is false, the other is true) and weak (if one is true, the other is false) links between them.
+pub(super) struct LinkGraph {
+    pub nodes: Vec<Node>,
+    pub strong: Vec<Vec<usize>>,
+    pub weak: Vec<Vec<usize>>,
+    // conflicts holds, for each node and digit, the cells that cannot be the digit if the node is true.
+    conflicts: Vec<[u128; 10]>,
+}
+
+impl Node {
+    pub fn points(&self) -> Vec<Point> {
+        points(self.cells)
+    }
+}
+
+impl LinkGraph {
+    pub fn new(grid: &Grid, rules: Rules) -> LinkGraph {
+        let digits: Vec<usize> = match rules.digit {
+            Some(d) => vec![d],
+            None => ALL_DIGITS.collect(),
+        };
+
+        let mut nodes = Vec::<Node>::new();
+        for_all_cells!(r, c, {
+            let cell = grid.cells[r][c];
+            if cell.count() < 2 {
+                continue;
+            }
+
+            for &d in &digits {
+                if cell.0 & (1 << d) != 0 {
+                    nodes.push(Node { digit: d, cells: bit(&(r, c)) });
+                }
+            }
+        });
+
+        if rules.grouped {
+            for b in &BOX.cells {
+                for line in &[&*COL, &*ROW] {
+                    for u in &line.cells {
+                        let shared = mask(b) & mask(u);
+                        if shared == 0 {
+                            continue;
+                        }
+
+                        for &d in &digits {
+                            let cells = shared & grid.places(d, 2);
+                            if cells.count_ones() > 1 {
+                                nodes.push(Node { digit: d, cells });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let conflicts: Vec<[u128; 10]> = nodes
+            .iter()
+            .map(|n| {
+                let mut masks = [0; 10];
+                masks[n.digit] = n.points().iter().fold(!0, |acc, p| acc & VISIBLE[p.0][p.1]);
+                if n.cells.count_ones() == 1 {
+                    for (d, m) in masks.iter_mut().enumerate() {
+                        if d != n.digit {
+                            *m = n.cells;
+                        }
+                    }
+                }
+                masks
+            })
+            .collect();
+
+        let mut strong = vec![Vec::new(); nodes.len()];
+        let mut weak = vec![Vec::new(); nodes.len()];
+
+        for (ai, a) in nodes.iter().enumerate() {
+            for (bi, b) in nodes.iter().enumerate() {
+                if ai == bi {
+                    continue;
+                }
+
+                let weak_link = if a.digit == b.digit {
+                    b.cells & !conflicts[ai][a.digit] == 0
+                } else {
+                    rules.cell_weak && a.cells == b.cells && a.cells.count_ones() == 1
+                };
+                if weak_link {
+                    weak[ai].push(bi);
+                }
+
+                if rules.bi_value && a.digit != b.digit && a.cells == b.cells && a.cells.count_ones() == 1 && grid[&a.points()[0]].count() == 2 {
+                    strong[ai].push(bi);
+                }
+            }
+        }
+
+        if rules.bi_location {
+            for group in &[&*BOX, &*COL, &*ROW] {
+                for u in &group.cells {
+                    let unit = mask(u);
+                    for &d in &digits {
+                        let places = unit & grid.places(d, 1);
+                        let inside: Vec<usize> = (0..nodes.len()).filter(|&i| nodes[i].digit == d && nodes[i].cells & !unit == 0).collect();
+
+                        for &ai in &inside {
+                            for &bi in &inside {
+                                let (a, b) = (nodes[ai].cells, nodes[bi].cells);
+                                if a & b == 0 && a | b == places && !strong[ai].contains(&bi) {
+                                    strong[ai].push(bi);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        LinkGraph { nodes, strong, weak, conflicts }
+    }
+
+    // eliminations returns, for each digit, the cells that cannot be the digit if at least one of the two nodes is true.
+    fn eliminations(&self, a: usize, b: usize, candidates: &[u128; 10]) -> [u128; 10] {
+        let mut masks = [0; 10];
+        for d in ALL_DIGITS {
+            masks[d] = self.conflicts[a][d] & self.conflicts[b][d] & candidates[d];
+        }
+        masks
+    }
+
+    // describe formats a chain, showing strong links as "=" and weak links as "-".
+    fn describe(&self, path: &[usize]) -> String {
+        let mut s = String::new();
+        for (i, n) in path.iter().enumerate() {
+            if i > 0 {
+                s += if i % 2 == 1 { " = " } else { " - " };
+            }
+            s += &format!("{}{:?}", self.nodes[*n].digit, self.nodes[*n].points());
+        }
+        s
+    }
+
+    // pattern returns the cells and digits of the nodes of a path.
+    fn pattern(&self, path: &[usize]) -> (Vec<Point>, Cell) {
+        let cells = path.iter().fold(0, |acc, n| acc | self.nodes[*n].cells);
+        let digits = path.iter().fold(Cell(0), |acc, n| acc | Cell(1 << self.nodes[*n].digit));
+        (points(cells), digits)
+    }
+}
+
+impl Grid {
+    // aic removes candidates. An alternating inference chain joins nodes (a digit in a cell, or in a group of cells in a box and a row or column) by alternating strong links (if one node is false, the other is true) and weak links (if one node is true, the other is false), starting and ending with a strong link. If the first node is false, the last is true, so any candidate that would be removed by both of them can be removed. If the chain returns to its first node, that node must be true. If the last node is weakly linked to the first, the chain is a continuous loop and every weak link in it becomes strong, so any candidate that would be removed by both nodes of a weak link can be removed. Chains are limited to max_chain strong links. It returns true if it changes any cells.
+    pub fn aic(&mut self) -> bool {
+        let rules = Rules {
+            digit: None,
+            bi_location: true,
+            bi_value: true,
+            grouped: true,
+            cell_weak: true,
+        };
+
+        // Each strong link joins two nodes.
+        self.chain("aic", rules, self.max_chain * 2)
+    }
+
+    // chain builds a link graph following the rules and searches it, breadth first from each node, for the shortest chain of at most `limit` nodes that removes candidates. It returns true if it changes any cells.
+    pub(super) fn chain(&mut self, name: &str, rules: Rules, limit: usize) -> bool {
+        let graph = LinkGraph::new(self, rules);
+        let mut candidates = [0; 10];
+        for (d, m) in candidates.iter_mut().enumerate().skip(1) {
+            *m = self.places(d, 1);
+        }
+
+        for start in 0..graph.nodes.len() {
+            // A state is a node and whether the chain makes it true (1) or false (0). The chain begins with the start node false.
+            let mut parent: Vec<Option<usize>> = vec![None; graph.nodes.len() * 2];
+            let mut length = vec![0; graph.nodes.len() * 2];
+            let mut queue = VecDeque::new();
+            length[start * 2] = 1;
+            queue.push_back(start * 2);
+
+            while let Some(state) = queue.pop_front() {
+                if length[state] >= limit {
+                    continue;
+                }
+
+                let (node, on) = (state / 2, state % 2 == 1);
+                let links = if on { &graph.weak[node] } else { &graph.strong[node] };
+
+                for &next in links {
+                    let next_state = next * 2 + if on { 0 } else { 1 };
+                    if length[next_state] != 0 {
+                        continue;
+                    }
+
+                    length[next_state] = length[state] + 1;
+                    parent[next_state] = Some(state);
+                    queue.push_back(next_state);
+
+                    if on {
+                        continue;
+                    }
+
+                    // The chain ends with a strong link, so either the start or the end is true.
+                    let mut path = vec![next_state];
+                    while let Some(p) = parent[path[path.len() - 1]] {
+                        path.push(p);
+                    }
+                    path.reverse();
+                    let nodes: Vec<usize> = path.iter().map(|s| s / 2).collect();
+
+                    let mut masks = graph.eliminations(start, next, &candidates);
+                    let mut kind = "chain";
+
+                    if masks.iter().all(|m| *m == 0) && graph.weak[next].contains(&start) && (1..nodes.len()).all(|i| !nodes[..i].contains(&nodes[i])) {
+                        // A continuous loop: each weak link, including the one back to the start, has exactly one true node.
+                        for i in (1..nodes.len()).step_by(2) {
+                            let (a, b) = (nodes[i], nodes[(i + 1) % nodes.len()]);
+                            let loop_masks = graph.eliminations(a, b, &candidates);
+                            for d in ALL_DIGITS {
+                                masks[d] |= loop_masks[d];
+                            }
+                        }
+                        kind = "continuous loop";
+                    }
+
+                    if masks.iter().all(|m| *m == 0) {
+                        continue;
+                    }
+
+                    let mut res = false;
+                    let (pattern, digits) = graph.pattern(&nodes);
+                    for d in ALL_DIGITS {
+                        for p in points(masks[d]) {
+                            if self[&p].and_not(&Cell(1 << d)) {
+                                cell_change!(self, res, &pattern, digits, "{} {} {} removes {} from {:?}", name, kind, graph.describe(&nodes), d, p);
+                            }
+                        }
+                    }
+
+                    if res {
+                        return res;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    // places returns the mask of the cells containing the digit that have at least `min` candidates.
+    pub(super) fn places(&self, d: usize, min: usize) -> u128 {
+        let mut cells = 0;
+        for_all_cells!(r, c, {
+            let cell = self.cells[r][c];
+            if cell.count() >= min && cell.0 & (1 << d) != 0 {
+                cells |= bit(&(r, c));
+            }
+        });
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::marked;
+    use super::*;
+
+    // skyscraper leaves 1 in column 0 only at (0, 0) and (5, 0), and in column 4 only at (0, 4) and (4, 4), giving the x-cycle (5, 0) = (0, 0) - (0, 4) = (4, 4).
+    fn skyscraper() -> Grid {
+        let marks: Vec<(Point, &str)> = (0..9)
+            .flat_map(|r| [(r, 0), (r, 4)])
+            .filter(|p| ![(0, 0), (5, 0), (0, 4), (4, 4)].contains(p))
+            .map(|p| (p, "23456789"))
+            .collect();
+        marked(&marks)
+    }
+
+    // xy_loop places the bi-value cells (0, 0) 12, (0, 4) 23, (4, 4) 34 and (4, 0) 41, an xy-chain of four cells from the 1 of (0, 0) to the 1 of (4, 0).
+    fn xy_loop() -> Grid {
+        marked(&[((0, 0), "12"), ((0, 4), "23"), ((4, 4), "34"), ((4, 0), "14")])
+    }
+
+    #[test]
+    fn x_cycle() {
+        let mut grid = skyscraper();
+        grid.max_chain = 2;
+        assert!(grid.x_cycles());
+        // Either (5, 0) or (4, 4) is the 1, so the cells that see both cannot be.
+        for p in [(4, 1), (4, 2), (5, 3), (5, 5)] {
+            assert_eq!(grid[&p].digits(), vec![2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+        assert_eq!(grid.cells[3][1].digits().len(), 9);
+        assert_eq!(grid.cells[5][0].digits().len(), 9);
+    }
+
+    #[test]
+    fn aic() {
+        let mut grid = skyscraper();
+        grid.max_chain = 2;
+        assert!(grid.aic());
+        assert_eq!(grid.cells[4][1].digits(), vec![2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn x_cycle_max_chain() {
+        let mut grid = skyscraper();
+        grid.max_chain = 1;
+        let before = grid.encode_candidates();
+        assert!(!grid.x_cycles());
+        assert_eq!(grid.encode_candidates(), before);
+    }
+
+    #[test]
+    fn xy_chain() {
+        let mut grid = xy_loop();
+        grid.max_chain = 4;
+        assert!(grid.xy_chain());
+        // Either (0, 0) or (4, 0) is the 1, so the rest of column 0 cannot be.
+        for r in [1, 2, 3, 5, 6, 7, 8] {
+            assert_eq!(grid.cells[r][0].digits(), vec![2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+        assert_eq!(grid.cells[0][0].digits(), vec![1, 2]);
+        assert_eq!(grid.cells[0][1].digits().len(), 9);
+    }
+
+    #[test]
+    fn xy_chain_max_chain() {
+        // Every chain of three cells or fewer starts and ends in cells that do not share a digit and a unit.
+        let mut grid = xy_loop();
+        grid.max_chain = 3;
+        let before = grid.encode_candidates();
+        assert!(!grid.xy_chain());
+        assert_eq!(grid.encode_candidates(), before);
+    }
+}
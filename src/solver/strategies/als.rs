@@ -0,0 +1,279 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, Point, ALL_DIGITS, BOX, COL, COLS, ROW, ROWS, VISIBLE};
+use super::{bit, points};
+use log::Level;
+use log::{info, log_enabled};
+use std::collections::HashSet;
+
+// An almost locked set (ALS) is N unsolved cells in one unit that together contain N + 1 digits. If any one of its digits is removed, the rest are locked into the set. places holds, for each digit, the cells of the set that contain it, and sees holds the cells that see all of those places.
+struct Als {
+    cells: u128,
+    digits: Cell,
+    places: [u128; 10],
+    sees: [u128; 10],
+}
+
+impl Als {
+    // restricted_common returns the digits that both sets contain where every place for the digit in one set sees every place in the other, so at most one of the sets can contain the digit. The sets must not overlap.
+    fn restricted_common(&self, other: &Als) -> Vec<usize> {
+        if self.cells & other.cells != 0 {
+            return Vec::new();
+        }
+
+        (self.digits & other.digits).digits().into_iter().filter(|&d| self.places[d] & !other.sees[d] == 0).collect()
+    }
+
+    fn points(&self) -> Vec<Point> {
+        points(self.cells)
+    }
+}
+
+impl Grid {
+    // als_xz removes candidates. If two almost locked sets A and B share a restricted common digit X (every X in A sees every X in B), X can be in at most one of them, so the other is locked. Then for any other digit Z in both sets, at least one of them contains Z, so Z can be removed from any cell that sees every Z in both sets. If the sets share two restricted common digits, both sets are locked: each digit other than the restricted ones can be removed from the cells that see all of its places in its set, and each restricted digit can be removed from the cells that see all of its places in both sets. It returns true if it changes any cells.
+    pub fn als_xz(&mut self) -> bool {
+        let sets = self.almost_locked_sets();
+        let candidates = self.candidate_places();
+
+        for (i, a) in sets.iter().enumerate() {
+            for b in &sets[i + 1..] {
+                let common = a.restricted_common(b);
+                if common.is_empty() {
+                    continue;
+                }
+
+                let mut res = false;
+                let pattern = points(a.cells | b.cells);
+                let mut remove = |grid: &mut Grid, d: usize, cells: u128, how: &str| {
+                    for p in points(cells & candidates[d]) {
+                        if grid[&p].and_not(&Cell(1 << d)) {
+                            cell_change!(
+                                grid,
+                                res,
+                                &pattern,
+                                a.digits | b.digits,
+                                "als-xz {:?} and {:?} with {:?} {}, removing {} from {:?}",
+                                a.points(),
+                                b.points(),
+                                common,
+                                how,
+                                d,
+                                p
+                            );
+                        }
+                    }
+                };
+
+                for z in (a.digits & b.digits).digits() {
+                    if common.len() > 1 || !common.contains(&z) {
+                        remove(self, z, a.sees[z] & b.sees[z], "shared by both sets");
+                    }
+                }
+
+                if common.len() > 1 {
+                    for set in [a, b] {
+                        for d in set.digits.digits() {
+                            if !common.contains(&d) {
+                                remove(self, d, set.sees[d], "locked in one set");
+                            }
+                        }
+                    }
+                }
+
+                if res {
+                    return res;
+                }
+            }
+        }
+
+        false
+    }
+
+    // als_xy_wing removes candidates. If almost locked set C shares restricted common digit X with set A and a different restricted common digit Y with set B, C cannot lose both X and Y, so A or B must be locked. Then for any digit Z in both A and B (other than X and Y), at least one of them contains Z, so Z can be removed from any cell that sees every Z in both sets. It returns true if it changes any cells.
+    pub fn als_xy_wing(&mut self) -> bool {
+        let sets = self.almost_locked_sets();
+        let candidates = self.candidate_places();
+
+        for c in &sets {
+            let links: Vec<(&Als, usize)> = sets.iter().flat_map(|s| c.restricted_common(s).into_iter().map(move |d| (s, d))).collect();
+
+            for (i, (a, x)) in links.iter().enumerate() {
+                for (b, y) in &links[i + 1..] {
+                    if x == y || a.cells & b.cells != 0 {
+                        continue;
+                    }
+
+                    let mut res = false;
+                    for z in (a.digits & b.digits).digits() {
+                        if z == *x || z == *y {
+                            continue;
+                        }
+
+                        for p in points(a.sees[z] & b.sees[z] & candidates[z]) {
+                            if self[&p].and_not(&Cell(1 << z)) {
+                                cell_change!(
+                                    self,
+                                    res,
+                                    &points(a.cells | b.cells | c.cells),
+                                    a.digits | b.digits | c.digits,
+                                    "als-xy-wing {:?} and {:?} with pivot {:?} on {} and {}, removing {} from {:?}",
+                                    a.points(),
+                                    b.points(),
+                                    c.points(),
+                                    x,
+                                    y,
+                                    z,
+                                    p
+                                );
+                            }
+                        }
+                    }
+
+                    if res {
+                        return res;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    // death_blossom removes candidates. A stem cell with digits D1 to Dn is paired with almost locked sets (petals), one per digit, where every place for Di in petal i sees the stem. Whichever digit the stem is, the matching petal loses it and is locked. If some digit Z, not in the stem, is in every petal, at least one petal contains Z, so Z can be removed from any cell that sees every Z in every petal. It returns true if it changes any cells.
+    pub fn death_blossom(&mut self) -> bool {
+        let sets = self.almost_locked_sets();
+        let candidates = self.candidate_places();
+
+        for_all_cells!(r, c, {
+            let stem = self.cells[r][c];
+            if stem.count() < 2 || stem.count() > 3 {
+                continue;
+            }
+
+            for z in ALL_DIGITS {
+                if stem.0 & (1 << z) != 0 {
+                    continue;
+                }
+
+                let petals: Vec<Vec<&Als>> = stem
+                    .digits()
+                    .iter()
+                    .map(|&d| {
+                        sets.iter()
+                            .filter(|s| s.cells & bit(&(r, c)) == 0 && s.digits.0 & (1 << z) != 0 && s.places[d] != 0 && s.places[d] & !VISIBLE[r][c] == 0)
+                            .collect()
+                    })
+                    .collect();
+
+                if let Some(chosen) = Self::death_blossom_petals(&petals, &mut Vec::new(), z, candidates[z]) {
+                    let targets = chosen.iter().fold(candidates[z], |acc, s| acc & s.sees[z]);
+                    let mut res = false;
+                    for p in points(targets) {
+                        if self[&p].and_not(&Cell(1 << z)) {
+                            cell_change!(
+                                self,
+                                res,
+                                &points(chosen.iter().fold(bit(&(r, c)), |acc, s| acc | s.cells)),
+                                stem | Cell(1 << z),
+                                "death blossom with stem ({}, {}) and petals {:?} removes {} from {:?}",
+                                r,
+                                c,
+                                chosen.iter().map(|s| s.points()).collect::<Vec<Vec<Point>>>(),
+                                z,
+                                p
+                            );
+                        }
+                    }
+
+                    if res {
+                        return res;
+                    }
+                }
+            }
+        });
+
+        false
+    }
+
+    // death_blossom_petals chooses one petal for each digit of the stem, keeping only choices where some cell with z sees every z in the chosen petals.
+    fn death_blossom_petals<'a>(petals: &[Vec<&'a Als>], chosen: &mut Vec<&'a Als>, z: usize, targets: u128) -> Option<Vec<&'a Als>> {
+        if targets == 0 {
+            return None;
+        }
+
+        if chosen.len() == petals.len() {
+            return Some(chosen.clone());
+        }
+
+        for petal in &petals[chosen.len()] {
+            chosen.push(petal);
+            let found = Self::death_blossom_petals(petals, chosen, z, targets & petal.sees[z]);
+            chosen.pop();
+
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        None
+    }
+
+    // almost_locked_sets finds every almost locked set in every unit. A set in more than one unit (a row or column and a box) is only returned once.
+    fn almost_locked_sets(&self) -> Vec<Als> {
+        let mut sets = Vec::new();
+        let mut seen = HashSet::new();
+
+        for group in &[&*BOX, &*COL, &*ROW] {
+            for u in &group.cells {
+                let open: Vec<&Point> = u.iter().filter(|p| self[p].count() > 1).collect();
+
+                for subset in 1..1_usize << open.len() {
+                    let members: Vec<&Point> = (0..open.len()).filter(|i| subset & (1 << i) != 0).map(|i| open[i]).collect();
+                    let digits = members.iter().fold(Cell(0), |acc, p| acc | self[p]);
+                    if digits.count() != members.len() + 1 {
+                        continue;
+                    }
+
+                    let cells = members.iter().fold(0, |acc, p| acc | bit(p));
+                    if !seen.insert(cells) {
+                        continue;
+                    }
+
+                    let mut places = [0; 10];
+                    let mut sees = [0; 10];
+                    for d in digits.digits() {
+                        places[d] = members.iter().filter(|p| self[p].0 & (1 << d) != 0).fold(0, |acc, p| acc | bit(p));
+                        sees[d] = points(places[d]).iter().fold(!0, |acc, p| acc & VISIBLE[p.0][p.1]);
+                    }
+
+                    sets.push(Als { cells, digits, places, sees });
+                }
+            }
+        }
+
+        sets
+    }
+
+    // candidate_places returns, for each digit, the mask of the unsolved cells that contain it.
+    fn candidate_places(&self) -> [u128; 10] {
+        let mut candidates = [0; 10];
+        for (d, m) in candidates.iter_mut().enumerate().skip(1) {
+            *m = self.places(d, 2);
+        }
+        candidates
+    }
+}
@@ -14,53 +14,45 @@
  * limitations under the License.
  */
 
-use super::super::{Cell, Grid, Group, Point, ALL_DIGITS, BOX, COL, ROW};
+use super::super::{Cell, Grid, Group, Point, ALL_DIGITS, COL, ROW};
+use super::intersections;
 use log::Level;
 use log::{info, log_enabled};
 
 impl Grid {
     // box_line removes candidates. When a candidate within a column or row appears only in a single box that candidate can be removed from all cells in the box, other than those in the column or row. It returns true if it changes any cells.
     pub fn box_line(&mut self) -> bool {
-        let col = |p: &Point| p.1;
-        let row = |p: &Point| p.0;
-
-        self.box_line_group(&COL, col, row, |i, c| i * 3 + c / 3) || self.box_line_group(&ROW, row, col, |i, r| r / 3 * 3 + i)
+        self.box_line_group(&COL) || self.box_line_group(&ROW)
     }
 
-    fn box_line_group(&mut self, group: &Group, major: fn(&Point) -> usize, minor: fn(&Point) -> usize, box_sel: fn(usize, usize) -> usize) -> bool {
-        group_loop!(self, res, group, ci, c, {
-            let mut boxes = [[false; 3]; 10];
-            for p in c {
-                let cell = self[p];
-                for d in ALL_DIGITS {
-                    if cell.0 & (1 << d) != 0 {
-                        boxes[d][minor(p) / 3] = true;
-                    }
-                }
-            }
-
+    fn box_line_group(&mut self, group: &Group) -> bool {
+        let mut res = false;
+        for i in intersections(group) {
             for d in ALL_DIGITS {
-                let index = if boxes[d][0] && !boxes[d][1] && !boxes[d][2] {
-                    0
-                } else if !boxes[d][0] && boxes[d][1] && !boxes[d][2] {
-                    1
-                } else if !boxes[d][0] && !boxes[d][1] && boxes[d][2] {
-                    2
-                } else {
+                let bit = Cell(1 << d);
+                if !i.middle.iter().any(|p| self[p].0 & bit.0 != 0) || i.line_rest.iter().any(|p| self[p].0 & bit.0 != 0) {
                     continue;
-                };
-
-                for pi in 0..9 {
-                    let p = BOX.cells[box_sel(index, ci)][pi];
-                    if major(&p) == major(&c[index]) {
-                        continue;
-                    }
+                }
 
-                    if self[&p].and_not(&Cell(1 << d)) {
-                        cell_change!(self, res, "all {}'s in {} {} appear in box {} removing from {:?}", d, group.name, ci, box_sel(index, ci), p);
+                let pattern: Vec<Point> = i.middle.iter().copied().filter(|p| self[p].0 & bit.0 != 0).collect();
+                for p in &i.box_rest {
+                    if self[p].and_not(&bit) {
+                        cell_change!(
+                            self,
+                            res,
+                            &pattern,
+                            bit,
+                            "all {}'s in {} {} appear in box {} removing from {:?}",
+                            d,
+                            group.name,
+                            i.line_index,
+                            i.box_index,
+                            p
+                        );
                     }
                 }
             }
-        })
+        }
+        res
     }
 }
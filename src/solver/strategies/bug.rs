@@ -0,0 +1,189 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{box_of, Cell, Grid, Point, ALL_DIGITS, BOX, COL, COLS, ROW, ROWS};
+use super::neighbors;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // bug_1 solves a cell. A bivalue universal grave (BUG) is a grid where every unsolved cell has two candidates and every candidate appears exactly twice in each of its units; it has either no solution or two, so it cannot occur in a puzzle with a single solution. If every unsolved cell has two candidates except one with three, the digit that would be left over in that cell must be the solution to the cell. It returns true if it changes any cells.
+    pub fn bug_1(&mut self) -> bool {
+        let mut res = false;
+
+        if let Some(candidates) = self.bug_candidates() {
+            if candidates.len() == 1 {
+                let (p, d) = candidates[0];
+                if self[&p].replace(&Cell(1 << d)) {
+                    cell_change!(self, res, &[p], Cell(1 << d), "bug+1 sets {:?} to {}", p, d);
+                }
+            }
+        }
+
+        res
+    }
+
+    // bug_n removes candidates. If removing more than one candidate would leave a bivalue universal grave, at least one of those candidates must be true. If they are all in one cell, the other candidates can be removed from that cell. If they are all the same digit, that digit can be removed from any cell that sees all of them. It returns true if it changes any cells.
+    pub fn bug_n(&mut self) -> bool {
+        let mut res = false;
+
+        let candidates = match self.bug_candidates() {
+            Some(candidates) if candidates.len() > 1 => candidates,
+            _ => return false,
+        };
+
+        let p = candidates[0].0;
+        if candidates.iter().all(|(q, _)| *q == p) {
+            let bits = Cell(candidates.iter().fold(0, |acc, (_, d)| acc | 1 << d));
+            if self[&p].and(&bits) {
+                cell_change!(self, res, &[p], bits, "bug+{} {:?} limits {:?} to {}", candidates.len(), candidates, p, bits);
+            }
+        }
+
+        let d = candidates[0].1;
+        if candidates.iter().all(|(_, e)| *e == d) {
+            let pattern: Vec<Point> = candidates.iter().map(|&(q, _)| q).collect();
+            let ns: Vec<[[bool; COLS]; ROWS]> = candidates.iter().map(|(q, _)| neighbors(q)).collect();
+            for_all_cells!(r, c, {
+                if ns.iter().all(|n| n[r][c]) && self.cells[r][c].and_not(&Cell(1 << d)) {
+                    cell_change!(self, res, &pattern, Cell(1 << d), "bug+{} {:?} removes {} from ({}, {})", candidates.len(), candidates, d, r, c);
+                }
+            });
+        }
+
+        res
+    }
+
+    // bug_candidates finds the candidates in cells with more than two candidates that appear more than twice in each of the cell's units. If removing them would leave a bivalue universal grave, it returns them, if the puzzle is assumed to have a single solution.
+    fn bug_candidates(&self) -> Option<Vec<(Point, usize)>> {
+        if !self.assume_unique {
+            return None;
+        }
+
+        let counts = |unit: &[Point; 9], d: usize| unit.iter().filter(|p| self[p].count() > 1 && self[p].0 & (1 << d) != 0).count();
+
+        let mut candidates = Vec::new();
+        let mut grave = self.cells;
+        for_all_cells!(r, c, {
+            let cell = self.cells[r][c];
+            if cell.count() < 2 {
+                continue;
+            }
+
+            if cell.count() > 2 {
+                for d in cell.digits() {
+                    if counts(&ROW.cells[r], d) > 2 && counts(&COL.cells[c], d) > 2 && counts(&BOX.cells[box_of(r, c)], d) > 2 {
+                        candidates.push(((r, c), d));
+                        grave[r][c].and_not(&Cell(1 << d));
+                    }
+                }
+            }
+
+            if grave[r][c].count() != 2 {
+                return None;
+            }
+        });
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        for group in &[&*BOX, &*COL, &*ROW] {
+            for u in &group.cells {
+                for d in ALL_DIGITS {
+                    let n = u.iter().filter(|p| self[p].count() > 1 && grave[p.0][p.1].0 & (1 << d) != 0).count();
+                    if n != 0 && n != 2 {
+                        return None;
+                    }
+                }
+            }
+        }
+
+        Some(candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::marked;
+    use super::*;
+
+    // grave builds a bivalue universal grave on the solution (r * 3 + r / 3 + c) % 9 + 1. The cells solved as 1 to 4 are left with that digit and the next one in the cycle 1, 2, 3, 4, so each of those digits is in exactly two unsolved cells of every unit. The extra candidates are added to their cells.
+    fn grave(extras: &[(Point, &str)]) -> Grid {
+        let mut marks = Vec::new();
+        for_all_cells!(r, c, {
+            let digit = (r * 3 + r / 3 + c) % 9 + 1;
+            let mut m = digit.to_string();
+            if digit <= 4 {
+                m += &(digit % 4 + 1).to_string();
+            }
+            if let Some((_, extra)) = extras.iter().find(|(p, _)| *p == (r, c)) {
+                m += extra;
+            }
+            marks.push(((r, c), m));
+        });
+
+        let marks: Vec<(Point, &str)> = marks.iter().map(|(p, m)| (*p, m.as_str())).collect();
+        let mut grid = marked(&marks);
+        grid.assume_unique = true;
+        grid
+    }
+
+    #[test]
+    fn no_extra_candidates() {
+        let mut grid = grave(&[]);
+        assert!(!grid.bug_1() && !grid.bug_n());
+    }
+
+    #[test]
+    fn bug_1() {
+        // (0, 0) holds 1 and 2 in the grave, and the 3 added to it is the third 3 in its row, column and box.
+        let mut grid = grave(&[((0, 0), "3")]);
+        assert!(!grid.bug_n());
+        assert!(grid.bug_1());
+        assert_eq!(grid.cells[0][0].digits(), vec![3]);
+    }
+
+    #[test]
+    fn bug_1_needs_assume_unique() {
+        let mut grid = grave(&[((0, 0), "3")]);
+        grid.assume_unique = false;
+        assert!(!grid.bug_1());
+        assert_eq!(grid.cells[0][0].digits(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn bug_n_in_one_cell() {
+        let mut grid = grave(&[((0, 0), "34")]);
+        assert!(!grid.bug_1());
+        assert!(grid.bug_n());
+        assert_eq!(grid.cells[0][0].digits(), vec![3, 4]);
+    }
+
+    #[test]
+    fn bug_n_one_digit() {
+        // One of (0, 0) and (1, 0) is the 3, so the other 3s of column 0 and box 0 can go.
+        let mut grid = grave(&[((0, 0), "3"), ((1, 0), "3")]);
+        assert!(!grid.bug_1());
+        assert!(grid.bug_n());
+        assert_eq!(grid.cells[0][1].digits(), vec![2]);
+        assert_eq!(grid.cells[0][2].digits(), vec![4]);
+        assert_eq!(grid.cells[3][0].digits(), vec![2]);
+        assert_eq!(grid.cells[6][0].digits(), vec![4]);
+        assert_eq!(grid.cells[0][0].digits(), vec![1, 2, 3]);
+        assert_eq!(grid.cells[1][8].digits(), vec![3, 4]);
+    }
+}
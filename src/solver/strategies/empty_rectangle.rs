@@ -0,0 +1,99 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, ALL_DIGITS, BOX, COL, ROW};
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // empty_rectangle removes candidates. If all of the places for a digit in a box lie in one row and one column of the box (but not all in one of them), the digit must be in that row or that column of the box. If a column outside of the box has only two places for the digit, one of them in the box's row, the cell in the box's column and the other place's row cannot be the digit: if it were, the other place would not be, so the place in the box's row would be, leaving no place for the digit in the box. The same logic applies if rows and columns are swapped. It returns true if it changes any cells.
+    pub fn empty_rectangle(&mut self) -> bool {
+        let mut res = false;
+
+        for (bi, b) in BOX.cells.iter().enumerate() {
+            let points = self.digit_points(b);
+
+            for d in ALL_DIGITS {
+                let places = &points[d];
+                if places.len() < 2 || places.iter().all(|p| p.0 == places[0].0) || places.iter().all(|p| p.1 == places[0].1) {
+                    continue;
+                }
+
+                for r in bi / 3 * 3..bi / 3 * 3 + 3 {
+                    for c in bi % 3 * 3..bi % 3 * 3 + 3 {
+                        if !places.iter().all(|p| p.0 == r || p.1 == c) {
+                            continue;
+                        }
+
+                        // A conjugate pair in a column outside of the box, with one end in the box's row.
+                        for c2 in (0..9).filter(|c2| c2 / 3 != bi % 3) {
+                            let pair = &self.digit_points(&COL.cells[c2])[d];
+                            if pair.len() == 2 && pair.iter().any(|p| p.0 == r) {
+                                let other = if pair[0].0 == r { pair[1] } else { pair[0] };
+                                if other.0 / 3 != bi / 3 && self.cells[other.0][c].and_not(&Cell(1 << d)) {
+                                    cell_change!(
+                                        self,
+                                        res,
+                                        &[places.as_slice(), pair.as_slice()].concat(),
+                                        Cell(1 << d),
+                                        "empty rectangle in box {} on ({}, {}) with {:?} removes {} from ({}, {})",
+                                        bi,
+                                        r,
+                                        c,
+                                        pair,
+                                        d,
+                                        other.0,
+                                        c
+                                    );
+                                }
+                            }
+                        }
+
+                        // A conjugate pair in a row outside of the box, with one end in the box's column.
+                        for r2 in (0..9).filter(|r2| r2 / 3 != bi / 3) {
+                            let pair = &self.digit_points(&ROW.cells[r2])[d];
+                            if pair.len() == 2 && pair.iter().any(|p| p.1 == c) {
+                                let other = if pair[0].1 == c { pair[1] } else { pair[0] };
+                                if other.1 / 3 != bi % 3 && self.cells[r][other.1].and_not(&Cell(1 << d)) {
+                                    cell_change!(
+                                        self,
+                                        res,
+                                        &[places.as_slice(), pair.as_slice()].concat(),
+                                        Cell(1 << d),
+                                        "empty rectangle in box {} on ({}, {}) with {:?} removes {} from ({}, {})",
+                                        bi,
+                                        r,
+                                        c,
+                                        pair,
+                                        d,
+                                        r,
+                                        other.1
+                                    );
+                                }
+                            }
+                        }
+
+                        if res {
+                            return res;
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{box_of, count, Cell, Grid, Group, Point, ALL_DIGITS, COL, ROW};
+use super::combinations;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // x_wing removes candidates. If in 2 columns, say 0 and 7, all instances of a particular digit, say 4, appear in the same two rows, say 4 and 6, then 1 of the 4's must be in (0, 4) or (0, 6) and the other in (7, 4) or (7, 6). Therefore all of the other 4's in those two rows can be removed. The same logic applies if rows and columns are swapped. It returns true if it changes any cells.
+    pub fn x_wing(&mut self) -> bool {
+        self.fish_group(&COL, &ROW, 2) || self.fish_group(&ROW, &COL, 2)
+    }
+
+    // swordfish is the 3 column (or row) version of x_wing: if all instances of a digit in 3 columns appear in only 3 rows, the digit can be removed from the rest of those rows. It returns true if it changes any cells.
+    pub fn swordfish(&mut self) -> bool {
+        self.fish_group(&COL, &ROW, 3) || self.fish_group(&ROW, &COL, 3)
+    }
+
+    // jellyfish is the 4 column (or row) version of x_wing. It returns true if it changes any cells.
+    pub fn jellyfish(&mut self) -> bool {
+        self.fish_group(&COL, &ROW, 4) || self.fish_group(&ROW, &COL, 4)
+    }
+
+    // finned_x_wing removes candidates. A finned x_wing is an x_wing where one of the base columns (or rows) has extra candidates, the fins, all in a single box. Either a fin is true or the x_wing is, so any cell in the cover rows (or columns) that is in the same box as the fins can have the digit removed. It returns true if it changes any cells.
+    pub fn finned_x_wing(&mut self) -> bool {
+        self.finned_fish_group(&COL, &ROW, 2, false) || self.finned_fish_group(&ROW, &COL, 2, false)
+    }
+
+    // sashimi_x_wing is a finned_x_wing where a base column (or row) has only a single candidate outside of the fins. It returns true if it changes any cells.
+    pub fn sashimi_x_wing(&mut self) -> bool {
+        self.finned_fish_group(&COL, &ROW, 2, true) || self.finned_fish_group(&ROW, &COL, 2, true)
+    }
+
+    // finned_swordfish is the 3 column (or row) version of finned_x_wing. It returns true if it changes any cells.
+    pub fn finned_swordfish(&mut self) -> bool {
+        self.finned_fish_group(&COL, &ROW, 3, false) || self.finned_fish_group(&ROW, &COL, 3, false)
+    }
+
+    // sashimi_swordfish is the 3 column (or row) version of sashimi_x_wing. It returns true if it changes any cells.
+    pub fn sashimi_swordfish(&mut self) -> bool {
+        self.finned_fish_group(&COL, &ROW, 3, true) || self.finned_fish_group(&ROW, &COL, 3, true)
+    }
+
+    // finned_jellyfish is the 4 column (or row) version of finned_x_wing. It returns true if it changes any cells.
+    pub fn finned_jellyfish(&mut self) -> bool {
+        self.finned_fish_group(&COL, &ROW, 4, false) || self.finned_fish_group(&ROW, &COL, 4, false)
+    }
+
+    // sashimi_jellyfish is the 4 column (or row) version of sashimi_x_wing. It returns true if it changes any cells.
+    pub fn sashimi_jellyfish(&mut self) -> bool {
+        self.finned_fish_group(&COL, &ROW, 4, true) || self.finned_fish_group(&ROW, &COL, 4, true)
+    }
+
+    // group_places builds, for each unit of a group, the bitmap of the places in the unit where each digit appears.
+    pub(super) fn group_places(&self, group: &Group) -> [[u16; 10]; 9] {
+        let mut places = [[0; 10]; 9];
+        for (ui, u) in group.cells.iter().enumerate() {
+            places[ui] = self.digit_places(u);
+        }
+
+        places
+    }
+
+    // fish_group finds `size` units in the major (base) group where a digit appears only in the same `size` units of the minor (cover) group and removes that digit from the rest of the cover units.
+    fn fish_group(&mut self, major_group: &Group, minor_group: &Group, size: usize) -> bool {
+        let mut res = false;
+        let places = self.group_places(major_group);
+
+        for d in ALL_DIGITS {
+            let bases: Vec<usize> = (0..9).filter(|&ui| (2..=size).contains(&(count(places[ui][d]) as usize))).collect();
+
+            for units in combinations(&bases, size) {
+                let cover = units.iter().fold(0, |acc, &ui| acc | places[ui][d]);
+                if count(cover) as usize != size {
+                    continue;
+                }
+
+                let pattern = fish_pattern(major_group, &places, &units, d);
+                for minor in 0..9 {
+                    if cover & (1 << minor) == 0 {
+                        continue;
+                    }
+
+                    for (mi, m) in minor_group.cells[minor].iter().enumerate() {
+                        if units.contains(&mi) {
+                            continue;
+                        }
+
+                        if self[m].and_not(&Cell(1 << d)) {
+                            cell_change!(
+                                self,
+                                res,
+                                &pattern,
+                                Cell(1 << d),
+                                "in {}s {:?}, {} appears only in {} {} and {} other(s); removing from {:?}",
+                                major_group.name,
+                                units,
+                                d,
+                                minor_group.name,
+                                minor,
+                                size - 1,
+                                m
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // finned_fish_group finds `size` units in the major (base) group where a digit appears only in `size` units of the minor (cover) group plus some fins that all lie in one box. The digit is removed from the cells of the cover units that are outside of the base units and inside the fin box. When `sashimi` is set, only patterns where a base unit has fewer than 2 candidates in the cover units are used, otherwise only patterns where every base unit has at least 2.
+    fn finned_fish_group(&mut self, major_group: &Group, minor_group: &Group, size: usize, sashimi: bool) -> bool {
+        let mut res = false;
+        let places = self.group_places(major_group);
+
+        for d in ALL_DIGITS {
+            let bases: Vec<usize> = (0..9).filter(|&ui| places[ui][d] != 0).collect();
+
+            for units in combinations(&bases, size) {
+                let all = units.iter().fold(0, |acc, &ui| acc | places[ui][d]);
+                let extra = count(all) as usize;
+                if extra <= size || extra > size + 3 {
+                    continue;
+                }
+
+                // The fins lie in one box, so the minor units that are not covered must all be within one block of 3.
+                let mut candidates = Vec::new();
+                for b in 0..3 {
+                    let mask = all & (0b111 << (b * 3));
+                    let mut uncovered = mask;
+                    while uncovered != 0 {
+                        if count(all & !uncovered) as usize == size {
+                            candidates.push(all & !uncovered);
+                        }
+                        uncovered = (uncovered - 1) & mask;
+                    }
+                }
+
+                for cover in candidates {
+                    let covers: Vec<usize> = (0..9).filter(|&m| cover & (1 << m) != 0).collect();
+
+                    // The fins are the candidates in the base units that are outside of the cover units. They must all be in the same box.
+                    let mut fin_box = None;
+                    let mut boxed = true;
+                    for &ui in &units {
+                        for pi in 0..9 {
+                            if places[ui][d] & !cover & (1 << pi) == 0 {
+                                continue;
+                            }
+
+                            let p = major_group.cells[ui][pi];
+                            match fin_box {
+                                None => fin_box = Some(box_of(p.0, p.1)),
+                                Some(b) if b != box_of(p.0, p.1) => boxed = false,
+                                _ => {}
+                            }
+                        }
+                    }
+
+                    let fin_box = match fin_box {
+                        Some(b) if boxed => b,
+                        _ => continue,
+                    };
+
+                    if units.iter().any(|&ui| count(places[ui][d] & cover) < 2) != sashimi {
+                        continue;
+                    }
+
+                    let pattern = fish_pattern(major_group, &places, &units, d);
+                    for &minor in &covers {
+                        for (mi, m) in minor_group.cells[minor].iter().enumerate() {
+                            if units.contains(&mi) || box_of(m.0, m.1) != fin_box {
+                                continue;
+                            }
+
+                            if self[m].and_not(&Cell(1 << d)) {
+                                cell_change!(
+                                    self,
+                                    res,
+                                    &pattern,
+                                    Cell(1 << d),
+                                    "in {}s {:?}, {} appears only in {}s {:?} and box {}; removing from {:?}",
+                                    major_group.name,
+                                    units,
+                                    d,
+                                    minor_group.name,
+                                    covers,
+                                    fin_box,
+                                    m
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
+
+// fish_pattern returns the places of a digit in the base units of a fish, including any fins.
+fn fish_pattern(major_group: &Group, places: &[[u16; 10]; 9], units: &[usize], d: usize) -> Vec<Point> {
+    units
+        .iter()
+        .flat_map(|&ui| (0..9).filter(move |&pi| places[ui][d] & (1 << pi) != 0).map(move |pi| major_group.cells[ui][pi]))
+        .collect()
+}
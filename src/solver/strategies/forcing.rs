@@ -0,0 +1,198 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::step::without_notes;
+use super::super::{Cell, Grid, Point, Registry, ALL_DIGITS, BOX, COL, COLS, ROW, ROWS};
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // cell_forcing_chain removes candidates. Each digit of a cell is tried in turn on a copy of the grid, which is then reduced. One of the digits must be right, so any candidate that no consistent copy keeps can be removed. It returns true if it changes any cells.
+    pub fn cell_forcing_chain(&mut self, registry: &Registry) -> bool {
+        for_all_cells!(r, c, {
+            let cell = self.cells[r][c];
+            if cell.count() < 2 || cell.count() > 3 {
+                continue;
+            }
+
+            let branches: Vec<Point> = vec![(r, c); cell.count()];
+            if self.forcing_branches(registry, &branches, &cell.digits(), &format!("cell forcing chain on ({}, {})", r, c)) {
+                return true;
+            }
+        });
+
+        false
+    }
+
+    // unit_forcing_chain removes candidates. Each place for a digit in a unit is tried in turn on a copy of the grid, which is then reduced. One of the places must be right, so any candidate that no consistent copy keeps can be removed. It returns true if it changes any cells.
+    pub fn unit_forcing_chain(&mut self, registry: &Registry) -> bool {
+        for group in &[&*BOX, &*COL, &*ROW] {
+            for (ui, u) in group.cells.iter().enumerate() {
+                let points = self.digit_points(u);
+
+                for d in ALL_DIGITS {
+                    let places = &points[d];
+                    if places.len() < 2 || places.len() > 3 {
+                        continue;
+                    }
+
+                    if self.forcing_branches(registry, places, &vec![d; places.len()], &format!("unit forcing chain on {} in {} {}", d, group.name, ui)) {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    // nishio removes candidates. A candidate is tried on a copy of the grid, which is then reduced. If that leads to a contradiction (a cell with no candidates, or a digit with no place in a unit), the candidate can be removed. It returns true if it changes any cells.
+    pub fn nishio(&mut self, registry: &Registry) -> bool {
+        let mut res = false;
+
+        for_all_cells!(r, c, {
+            let cell = self.cells[r][c];
+            if cell.count() < 2 {
+                continue;
+            }
+
+            for d in cell.digits() {
+                if self.forcing_branch(registry, &(r, c), d).is_none() && self.cells[r][c].and_not(&Cell(1 << d)) {
+                    cell_change!(self, res, &[(r, c)], Cell(1 << d), "nishio: setting ({}, {}) to {} leads to a contradiction, removing", r, c, d);
+                    return res;
+                }
+            }
+        });
+
+        res
+    }
+
+    // forcing_branches tries each branch (setting a cell to a digit) on a copy of the grid. Branches that lead to a contradiction are dropped. Any candidate that is not kept by one of the remaining branches is removed.
+    fn forcing_branches(&mut self, registry: &Registry, places: &[Point], digits: &[usize], name: &str) -> bool {
+        let mut res = false;
+
+        let mut kept = [[Cell(0); COLS]; ROWS];
+        let mut consistent = false;
+        for (p, d) in places.iter().zip(digits) {
+            if let Some(copy) = self.forcing_branch(registry, p, *d) {
+                consistent = true;
+                for_all_cells!(r, c, {
+                    kept[r][c] = kept[r][c] | copy.cells[r][c];
+                });
+            }
+        }
+
+        // If every branch fails the grid itself is broken, and there is nothing to learn from it.
+        if !consistent {
+            return false;
+        }
+
+        let mut pattern = places.to_vec();
+        pattern.dedup();
+        let bits = digits.iter().fold(Cell(0), |acc, d| acc | Cell(1 << d));
+        for_all_cells!(r, c, {
+            let removed = self.cells[r][c] & !kept[r][c];
+            if removed.count() > 0 && self.cells[r][c].and_not(&removed) {
+                cell_change!(self, res, &pattern, bits, "{} removes {} from ({}, {})", name, removed, r, c);
+            }
+        });
+
+        res
+    }
+
+    // forcing_branch sets the cell to the digit on a copy of the grid and reduces it with the strategies of the registry up to the standard level. The cheap tiers find most contradictions and agreements, and the hard puzzles need nothing more, while reducing every branch with the extreme tier makes each forcing step many times slower. It returns the copy, or None if it leads to a contradiction.
+    fn forcing_branch(&self, registry: &Registry, p: &Point, d: usize) -> Option<Grid> {
+        let mut copy = *self;
+        copy[p] = Cell(1 << d);
+        without_notes(|| copy.reduce_to(registry, &mut None, &mut None, crate::Level::Standard));
+
+        if copy.contradiction() {
+            None
+        } else {
+            Some(copy)
+        }
+    }
+
+    // contradiction checks for a cell with no candidates, two cells solved with the same digit in a unit, or a digit with no place in a unit.
+    fn contradiction(&self) -> bool {
+        if self.empty_cell() {
+            return true;
+        }
+
+        for group in &[&*BOX, &*COL, &*ROW] {
+            for u in &group.cells {
+                let points = self.digit_points(u);
+                for d in ALL_DIGITS {
+                    if points[d].is_empty() || points[d].iter().filter(|p| self[p].count() == 1).count() > 1 {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::marked;
+    use super::*;
+
+    #[test]
+    fn nishio() {
+        // If (0, 0) were 1, (0, 1) and (0, 2) would both be 3.
+        let mut grid = marked(&[((0, 0), "12"), ((0, 1), "13"), ((0, 2), "13")]);
+        assert!(grid.nishio(&Registry::default()));
+        assert_eq!(grid.cells[0][0].digits(), vec![2]);
+        assert_eq!(grid.cells[0][1].digits(), vec![1, 3]);
+    }
+
+    #[test]
+    fn cell_forcing_chain_agreement() {
+        // Whichever of 1 and 2 (0, 0) is, it and (0, 1) and (1, 0) are solved as 1, 2 and 3 in some order, so the rest of box 0 cannot be any of them.
+        let mut grid = marked(&[((0, 0), "12"), ((0, 1), "13"), ((1, 0), "23")]);
+        assert!(grid.cell_forcing_chain(&Registry::default()));
+        assert_eq!(grid.cells[0][0].digits(), vec![1, 2]);
+        assert_eq!(grid.cells[2][2].digits(), vec![4, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[1][1].digits(), vec![4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn cell_forcing_chain_contradiction() {
+        // The branch setting (0, 0) to 1 fails, so only the branch setting it to 2 is kept.
+        let mut grid = marked(&[((0, 0), "12"), ((0, 1), "13"), ((0, 2), "13")]);
+        assert!(grid.cell_forcing_chain(&Registry::default()));
+        assert_eq!(grid.cells[0][0].digits(), vec![2]);
+        assert_eq!(grid.cells[1][1].digits(), vec![4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn contradiction() {
+        assert!(!marked(&[]).contradiction());
+        let mut grid = marked(&[]);
+        grid.cells[0][0] = Cell(0);
+        assert!(grid.contradiction());
+        // Two cells of row 0 are solved as 1.
+        let mut grid = marked(&[]);
+        grid.cells[0][1] = Cell(1 << 1);
+        grid.cells[0][5] = Cell(1 << 1);
+        assert!(grid.contradiction());
+        // No place is left for 9 in row 4.
+        let marks: Vec<(Point, &str)> = (0..9).map(|c| ((4, c), "12345678")).collect();
+        assert!(marked(&marks).contradiction());
+    }
+}
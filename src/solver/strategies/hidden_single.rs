@@ -32,7 +32,7 @@ impl Grid {
                 if points[d].len() == 1 {
                     let p = points[d][0];
                     if self[&p].replace(&Cell(1 << d)) {
-                        cell_change!(self, res, "in {} {} set {:?} to {}", group.name, ci, p, d);
+                        cell_change!(self, res, &[p], Cell(1 << d), "in {} {} set {:?} to {}", group.name, ci, p, d);
                     }
                 }
             }
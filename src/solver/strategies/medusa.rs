@@ -0,0 +1,177 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, Point, ALL_DIGITS, COLS, ROWS};
+use super::aic::{LinkGraph, Rules};
+use super::neighbors;
+use log::Level;
+use log::{info, log_enabled};
+
+// A candidate is a digit in a cell.
+type Candidate = (Point, usize);
+
+type Colors = [[[usize; 10]; COLS]; ROWS];
+
+impl Grid {
+    // medusa removes candidates. It extends singles_chain to all digits at once: the candidates of a cell with only two digits (bi-value) and the two places for a digit in a unit (bi-location) must have opposite values, so the candidates are alternately colored across digits. A color is false if it appears twice in a cell, appears twice for a digit in a unit, or would remove every candidate from some cell. Otherwise candidates can be removed from cells containing both colors, from cells that see a digit in both colors, and from cells that contain one color and see the digit in the other color. It returns true if it changes any cells.
+    pub fn medusa(&mut self) -> bool {
+        let rules = Rules {
+            digit: None,
+            bi_location: true,
+            bi_value: true,
+            grouped: false,
+            cell_weak: false,
+        };
+        let graph = LinkGraph::new(self, rules);
+        let mut links: [[[Vec<Candidate>; 10]; COLS]; ROWS] = Default::default();
+        for (ni, n) in graph.nodes.iter().enumerate() {
+            let p = n.points()[0];
+            for &mi in &graph.strong[ni] {
+                let m = &graph.nodes[mi];
+                links[p.0][p.1][n.digit].push((m.points()[0], m.digit));
+            }
+        }
+
+        let mut colors = [[[0; 10]; COLS]; ROWS];
+        let mut chain = 0;
+        for_all_cells!(r, c, {
+            for d in ALL_DIGITS {
+                if colors[r][c][d] != 0 || links[r][c][d].is_empty() {
+                    continue;
+                }
+
+                // Color each chain with its own pair of colors (chain * 2 + 1 and chain * 2 + 2).
+                chain += 1;
+                let mut candidates = vec![((r, c), d)];
+                let mut stack = vec![((r, c), d)];
+                colors[r][c][d] = chain * 2 + 1;
+                while let Some((p, pd)) = stack.pop() {
+                    for (q, qd) in &links[p.0][p.1][pd] {
+                        if colors[q.0][q.1][*qd] == 0 {
+                            colors[q.0][q.1][*qd] = if colors[p.0][p.1][pd] % 2 == 1 { chain * 2 + 2 } else { chain * 2 + 1 };
+                            candidates.push((*q, *qd));
+                            stack.push((*q, *qd));
+                        }
+                    }
+                }
+
+                if candidates.len() < 3 {
+                    continue;
+                }
+
+                if let Some(color) = self.medusa_false_color(&candidates, &colors, chain) {
+                    let mut res = false;
+                    let (pattern, digits) = medusa_pattern(&candidates);
+                    for (p, d) in &candidates {
+                        if colors[p.0][p.1][*d] == color && self[p].and_not(&Cell(1 << d)) {
+                            cell_change!(self, res, &pattern, digits, "medusa color {} is false, removing {} from {:?}", color, d, p);
+                        }
+                    }
+                    return res;
+                }
+
+                if self.medusa_eliminate(&candidates, &colors, chain) {
+                    return true;
+                }
+            }
+        });
+
+        false
+    }
+
+    // medusa_false_color checks rules 1 (twice in a cell), 2 (twice in a unit) and 6 (cell emptied by color) and returns the color that must be false, if any.
+    fn medusa_false_color(&self, candidates: &[Candidate], colors: &Colors, chain: usize) -> Option<usize> {
+        for (i, (p1, d1)) in candidates.iter().enumerate() {
+            let color = colors[p1.0][p1.1][*d1];
+            let n = neighbors(p1);
+            for (p2, d2) in candidates[i + 1..].iter() {
+                if colors[p2.0][p2.1][*d2] != color {
+                    continue;
+                }
+
+                if (p1 == p2 && d1 != d2) || (d1 == d2 && n[p2.0][p2.1]) {
+                    return Some(color);
+                }
+            }
+        }
+
+        for color in [chain * 2 + 1, chain * 2 + 2] {
+            for_all_cells!(r, c, {
+                let digits = self.cells[r][c].digits();
+                if digits.iter().any(|d| colors[r][c][*d] > chain * 2) {
+                    continue;
+                }
+
+                let n = neighbors(&(r, c));
+                if !digits.is_empty() && digits.iter().all(|d| candidates.iter().any(|(p, pd)| pd == d && n[p.0][p.1] && colors[p.0][p.1][*pd] == color)) {
+                    return Some(color);
+                }
+            });
+        }
+
+        None
+    }
+
+    // medusa_eliminate applies rules 3 (two colors in a cell), 4 (two colors elsewhere) and 5 (two colors unit + cell) to the uncolored candidates.
+    fn medusa_eliminate(&mut self, candidates: &[Candidate], colors: &Colors, chain: usize) -> bool {
+        let mut res = false;
+        let (pattern, digits) = medusa_pattern(candidates);
+
+        for_all_cells!(r, c, {
+            let n = neighbors(&(r, c));
+            let in_chain = |d: usize| colors[r][c][d] > chain * 2;
+            let cell_colors: Vec<usize> = ALL_DIGITS.filter(|d| in_chain(*d)).map(|d| colors[r][c][d]).collect();
+
+            for d in self.cells[r][c].digits() {
+                if in_chain(d) {
+                    continue;
+                }
+
+                if cell_colors.contains(&(chain * 2 + 1)) && cell_colors.contains(&(chain * 2 + 2)) {
+                    if self.cells[r][c].and_not(&Cell(1 << d)) {
+                        cell_change!(self, res, &pattern, digits, "({}, {}) contains both medusa colors, removing {}", r, c, d);
+                    }
+                    continue;
+                }
+
+                let seen: Vec<usize> = candidates.iter().filter(|(p, pd)| *pd == d && n[p.0][p.1]).map(|(p, pd)| colors[p.0][p.1][*pd]).collect();
+                if seen.contains(&(chain * 2 + 1)) && seen.contains(&(chain * 2 + 2)) {
+                    if self.cells[r][c].and_not(&Cell(1 << d)) {
+                        cell_change!(self, res, &pattern, digits, "({}, {}) sees {} in both medusa colors, removing", r, c, d);
+                    }
+                    continue;
+                }
+
+                if cell_colors.iter().any(|color| seen.iter().any(|s| s != color)) && self.cells[r][c].and_not(&Cell(1 << d)) {
+                    cell_change!(self, res, &pattern, digits, "({}, {}) contains one medusa color and sees {} in the other, removing", r, c, d);
+                }
+            }
+        });
+
+        res
+    }
+}
+
+// medusa_pattern returns the cells and digits of the candidates in a chain.
+fn medusa_pattern(candidates: &[Candidate]) -> (Vec<Point>, Cell) {
+    let mut pattern = Vec::new();
+    for (p, _) in candidates {
+        if !pattern.contains(p) {
+            pattern.push(*p);
+        }
+    }
+    (pattern, candidates.iter().fold(Cell(0), |acc, (_, d)| acc | Cell(1 << d)))
+}
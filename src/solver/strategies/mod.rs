@@ -14,24 +14,76 @@
  * limitations under the License.
  */
 
-use super::{box_of, Point, BOX, COL, COLS, ROW, ROWS};
+use super::{box_of, Group, Point, BOX, COL, COLS, ROW, ROWS};
 
+pub mod aic;
+pub mod als;
 pub mod box_line;
-pub mod hidden_pair;
-pub mod hidden_quad;
+pub mod bug;
+pub mod empty_rectangle;
+pub mod fish;
+pub mod forcing;
 pub mod hidden_single;
-pub mod hidden_triple;
-pub mod naked_pair;
+pub mod medusa;
 pub mod naked_single;
-pub mod naked_triple;
 pub mod pointing_line;
-pub mod x_wing;
+pub mod singles_chain;
+pub mod sk_loop;
+pub mod skyscraper;
+pub mod subset;
+pub mod sue_de_coq;
+pub mod unique_rectangle;
+pub mod wxyz_wing;
+pub mod x_cycles;
+pub mod xy_chain;
+pub mod xyz_wing;
 pub mod y_wing;
 
+// An intersection is the 3 cells shared by a box and a column or row, with the other cells of the line and of the box.
+struct Intersection {
+    box_index: usize,
+    line: &'static Group,
+    line_index: usize,
+    middle: Vec<Point>,
+    line_rest: Vec<Point>,
+    box_rest: Vec<Point>,
+}
+
+lazy_static! {
+    // INTERSECTIONS are the intersections of each box with the columns crossing it, then with the rows crossing it.
+    static ref INTERSECTIONS: Vec<Intersection> = {
+        let mut intersections = Vec::new();
+        for line in [&*COL, &*ROW] {
+            for (bi, b) in BOX.cells.iter().enumerate() {
+                for (li, l) in line.cells.iter().enumerate() {
+                    if !b.iter().any(|p| l.contains(p)) {
+                        continue;
+                    }
+
+                    intersections.push(Intersection {
+                        box_index: bi,
+                        line,
+                        line_index: li,
+                        middle: b.iter().filter(|p| l.contains(p)).copied().collect(),
+                        line_rest: l.iter().filter(|p| !b.contains(p)).copied().collect(),
+                        box_rest: b.iter().filter(|p| !l.contains(p)).copied().collect(),
+                    });
+                }
+            }
+        }
+        intersections
+    };
+}
+
+// intersections returns the intersections of the boxes with the lines of a group (columns or rows).
+fn intersections(line: &Group) -> impl Iterator<Item = &'static Intersection> + '_ {
+    INTERSECTIONS.iter().filter(move |i| i.line.name == line.name)
+}
+
 fn neighbors(curr: &Point) -> [[bool; COLS]; ROWS] {
     let mut points = [[false; COLS]; ROWS];
 
-    for u in vec![&BOX.cells[box_of(curr.0, curr.1)], &COL.cells[curr.1], &ROW.cells[curr.0]] {
+    for u in [&BOX.cells[box_of(curr.0, curr.1)], &COL.cells[curr.1], &ROW.cells[curr.0]] {
         for p in u {
             if p == curr {
                 continue;
@@ -43,3 +95,41 @@ fn neighbors(curr: &Point) -> [[bool; COLS]; ROWS] {
 
     points
 }
+
+// combinations returns all of the `size` element combinations of items, preserving the order of items.
+fn combinations(items: &[usize], size: usize) -> Vec<Vec<usize>> {
+    if size == 0 {
+        return vec![vec![]];
+    }
+
+    let mut res = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, *item);
+            res.push(rest);
+        }
+    }
+
+    res
+}
+
+// bit returns the mask of a cell, with bit r * 9 + c set for cell (r, c).
+fn bit(p: &Point) -> u128 {
+    1 << (p.0 * 9 + p.1)
+}
+
+// mask returns the mask of the cells of a unit.
+fn mask(unit: &[Point; 9]) -> u128 {
+    unit.iter().fold(0, |acc, p| acc | bit(p))
+}
+
+// points returns the cells in a mask.
+fn points(cells: u128) -> Vec<Point> {
+    let mut points = Vec::new();
+    for_all_cells!(r, c, {
+        if cells & bit(&(r, c)) != 0 {
+            points.push((r, c));
+        }
+    });
+    points
+}
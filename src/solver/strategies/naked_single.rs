@@ -38,7 +38,18 @@ impl Grid {
                     }
 
                     if self[p2].and_not(&cell) {
-                        cell_change!(self, res, "in {} {} cell {:?} allows only {}, removed from {:?}", group.name, ci, p1, cell.to_string(), p2);
+                        cell_change!(
+                            self,
+                            res,
+                            &[*p1],
+                            cell,
+                            "in {} {} cell {:?} allows only {}, removed from {:?}",
+                            group.name,
+                            ci,
+                            p1,
+                            cell.to_string(),
+                            p2
+                        );
                     }
                 }
             }
@@ -14,40 +14,31 @@
  * limitations under the License.
  */
 
-use super::super::{Cell, Grid, Point, ALL_DIGITS, BOX, COL, ROW};
+use super::super::{Cell, Grid, Group, Point, ALL_DIGITS, COL, ROW};
+use super::intersections;
 use log::Level;
 use log::{info, log_enabled};
 
 impl Grid {
-    // hidden_triple removes candidates. When a candidate within a box appears only in a single column or row, that candidate can be removed from all cells in the column or row outside of the box. It returns true if it changes any cells.
+    // pointing_line removes candidates. When a candidate within a box appears only in a single column or row, that candidate can be removed from all cells in the column or row outside of the box. It returns true if it changes any cells.
     pub fn pointing_line(&mut self) -> bool {
-        self.pointing_line_group("col", |p| COL.cells[p.1], |p| p.1) || self.pointing_line_group("row", |p| ROW.cells[p.0], |p| p.0)
+        self.pointing_line_group(&COL) || self.pointing_line_group(&ROW)
     }
 
-    fn pointing_line_group(&mut self, group: &str, sel: fn(&Point) -> [Point; 9], axis: fn(&Point) -> usize) -> bool {
+    fn pointing_line_group(&mut self, group: &Group) -> bool {
         let mut res = false;
-        for (bi, b) in BOX.cells.iter().enumerate() {
-            let points = self.digit_points(b);
-
-            // Loop through the digits and determine if all of them are on the same line (col or row). If so, then all other cells in that line that are not in the current box can have those digits removed.
-            'outer: for d in ALL_DIGITS {
-                if points[d].len() == 0 {
-                    return false;
-                }
-                let a = axis(&points[d][0]);
-                for p in points[d][1..].iter() {
-                    if axis(p) != a {
-                        continue 'outer;
-                    }
+        for i in intersections(group) {
+            // If all of the places for a digit in the box are in the line, all other cells in that line that are not in the box can have the digit removed.
+            for d in ALL_DIGITS {
+                let bit = Cell(1 << d);
+                if !i.middle.iter().any(|p| self[p].0 & bit.0 != 0) || i.box_rest.iter().any(|p| self[p].0 & bit.0 != 0) {
+                    continue;
                 }
 
-                for p in sel(&points[d][0]).iter() {
-                    if p.0 / 3 * 3 + p.1 / 3 == bi {
-                        continue;
-                    }
-
-                    if self[p].and_not(&Cell(1 << d)) {
-                        cell_change!(self, res, "in box {} removing {} from {:?} along {}", bi, d, p, group);
+                let pattern: Vec<Point> = i.middle.iter().copied().filter(|p| self[p].0 & bit.0 != 0).collect();
+                for p in &i.line_rest {
+                    if self[p].and_not(&bit) {
+                        cell_change!(self, res, &pattern, bit, "in box {} removing {} from {:?} along {}", i.box_index, d, p, group.name);
                     }
                 }
             }
@@ -0,0 +1,128 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, Point, ALL_DIGITS, BOX, COL, COLS, ROW, ROWS};
+use super::neighbors;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // singles_chain removes candidates. For a single digit, two cells that are the only places for the digit in a unit (a conjugate pair) must have opposite values: one is the digit and the other is not. Chaining conjugate pairs together and alternately coloring the cells gives two colors, one of which must be the digit. If two cells of the same color can "see" each other, that color is false and the digit is removed from all of its cells (color wrap). Any other cell that can see cells of both colors can have the digit removed (color trap). It returns true if it changes any cells.
+    pub fn singles_chain(&mut self) -> bool {
+        let mut res = false;
+
+        for d in ALL_DIGITS {
+            let links = self.conjugate_pairs(d);
+
+            let mut colors = [[0; COLS]; ROWS];
+            let mut chain = 0;
+            for_all_cells!(r, c, {
+                if colors[r][c] != 0 || links[r][c].is_empty() {
+                    continue;
+                }
+
+                // Color each chain with its own pair of colors (chain * 2 + 1 and chain * 2 + 2).
+                chain += 1;
+                let mut points = vec![(r, c)];
+                let mut stack = vec![(r, c)];
+                colors[r][c] = chain * 2 + 1;
+                while let Some(p) = stack.pop() {
+                    for q in &links[p.0][p.1] {
+                        if colors[q.0][q.1] == 0 {
+                            colors[q.0][q.1] = if colors[p.0][p.1] % 2 == 1 { chain * 2 + 2 } else { chain * 2 + 1 };
+                            points.push(*q);
+                            stack.push(*q);
+                        }
+                    }
+                }
+
+                if self.singles_chain_wrap(d, &points, &colors) || self.singles_chain_trap(d, &points, &colors) {
+                    res = true;
+                }
+            });
+        }
+
+        res
+    }
+
+    // conjugate_pairs finds, for each cell, the other cells that share a unit in which they are the only two places for the digit.
+    pub(super) fn conjugate_pairs(&self, d: usize) -> [[Vec<Point>; COLS]; ROWS] {
+        let mut links: [[Vec<Point>; COLS]; ROWS] = Default::default();
+        for group in &[&*BOX, &*COL, &*ROW] {
+            for u in &group.cells {
+                let points = self.digit_points(u);
+                if points[d].len() != 2 {
+                    continue;
+                }
+
+                let (p1, p2) = (points[d][0], points[d][1]);
+                if !links[p1.0][p1.1].contains(&p2) {
+                    links[p1.0][p1.1].push(p2);
+                    links[p2.0][p2.1].push(p1);
+                }
+            }
+        }
+
+        links
+    }
+
+    fn singles_chain_wrap(&mut self, d: usize, points: &[Point], colors: &[[usize; COLS]; ROWS]) -> bool {
+        let mut res = false;
+
+        for p1 in points {
+            let n = neighbors(p1);
+            for p2 in points {
+                if !n[p2.0][p2.1] || colors[p1.0][p1.1] != colors[p2.0][p2.1] {
+                    continue;
+                }
+
+                let color = colors[p1.0][p1.1];
+                for p in points {
+                    if colors[p.0][p.1] == color && self[p].and_not(&Cell(1 << d)) {
+                        cell_change!(self, res, points, Cell(1 << d), "{:?} and {:?} have the same color for {}, removing from {:?}", p1, p2, d, p);
+                    }
+                }
+
+                return res;
+            }
+        }
+
+        res
+    }
+
+    fn singles_chain_trap(&mut self, d: usize, points: &[Point], colors: &[[usize; COLS]; ROWS]) -> bool {
+        let mut res = false;
+
+        for_all_cells!(r, c, {
+            if colors[r][c] != 0 || self.cells[r][c].0 & (1 << d) == 0 {
+                continue;
+            }
+
+            let n = neighbors(&(r, c));
+            let seen: Vec<&Point> = points.iter().filter(|p| n[p.0][p.1]).collect();
+            for p1 in &seen {
+                if let Some(p2) = seen.iter().find(|p2| colors[p1.0][p1.1] != colors[p2.0][p2.1]) {
+                    if self.cells[r][c].and_not(&Cell(1 << d)) {
+                        cell_change!(self, res, points, Cell(1 << d), "({}, {}) sees {:?} and {:?} of opposite colors for {}, removing", r, c, p1, p2, d);
+                    }
+                    break;
+                }
+            }
+        });
+
+        res
+    }
+}
@@ -0,0 +1,166 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{box_of, count, Cell, Grid, Point, Unit, ALL_DIGITS, BOX, COL, ROW};
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // sk_loop removes candidates. Four cells at the corners of a rectangle, each in a different box, define 8 pairs of cells: in each box, the other 2 cells on the corner's row and the other 2 cells on the corner's column. Going around the rectangle, each pair is linked to the next pair by the row, column, or box that contains them both, and by the digits the two pairs have in common. If every candidate of every pair is in one of its two links and there are 16 linking digits in all, the 16 cells must use each linking digit exactly once in each link, so those digits can be removed from the rest of the linking row, column, or box. It returns true if it changes any cells.
+    pub fn sk_loop(&mut self) -> bool {
+        let mut res = false;
+
+        for r1 in 0..6 {
+            for r2 in (r1 / 3 + 1) * 3..9 {
+                for c1 in 0..6 {
+                    for c2 in (c1 / 3 + 1) * 3..9 {
+                        // The pairs in loop order and the units that link each pair to the next.
+                        let pairs = [
+                            Self::sk_loop_pair(r1, c1, true),
+                            Self::sk_loop_pair(r1, c2, true),
+                            Self::sk_loop_pair(r1, c2, false),
+                            Self::sk_loop_pair(r2, c2, false),
+                            Self::sk_loop_pair(r2, c2, true),
+                            Self::sk_loop_pair(r2, c1, true),
+                            Self::sk_loop_pair(r2, c1, false),
+                            Self::sk_loop_pair(r1, c1, false),
+                        ];
+                        let units: [&Unit; 8] = [
+                            &ROW.cells[r1],
+                            &BOX.cells[box_of(r1, c2)],
+                            &COL.cells[c2],
+                            &BOX.cells[box_of(r2, c2)],
+                            &ROW.cells[r2],
+                            &BOX.cells[box_of(r2, c1)],
+                            &COL.cells[c1],
+                            &BOX.cells[box_of(r1, c1)],
+                        ];
+
+                        if pairs.iter().any(|pair| pair.iter().any(|p| self[p].count() < 2)) {
+                            continue;
+                        }
+
+                        let candidates: Vec<Cell> = pairs.iter().map(|pair| self[&pair[0]] | self[&pair[1]]).collect();
+                        let links = match Self::sk_loop_links(&candidates) {
+                            Some(links) => links,
+                            None => continue,
+                        };
+
+                        let pattern: Vec<Point> = pairs.iter().flatten().copied().collect();
+                        let digits = links.iter().fold(Cell(0), |acc, &l| acc | l);
+                        for i in 0..8 {
+                            for p in units[i] {
+                                if pairs[i].contains(p) || pairs[(i + 1) % 8].contains(p) {
+                                    continue;
+                                }
+
+                                if self[p].and_not(&links[i]) {
+                                    cell_change!(
+                                        self,
+                                        res,
+                                        &pattern,
+                                        digits,
+                                        "sk loop on rows {}, {} and cols {}, {} links {:?} and {:?} with {}, removing from {:?}",
+                                        r1,
+                                        r2,
+                                        c1,
+                                        c2,
+                                        pairs[i],
+                                        pairs[(i + 1) % 8],
+                                        links[i],
+                                        p
+                                    );
+                                }
+                            }
+                        }
+
+                        if res {
+                            return res;
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // sk_loop_links chooses the digits that link each pair to the next so that every candidate of every pair is in one of its two links. For each digit this is the smallest set of links that covers all of the pairs containing the digit. It returns None unless there are exactly 16 linking digits.
+    fn sk_loop_links(candidates: &[Cell]) -> Option<[Cell; 8]> {
+        let mut links = [Cell(0); 8];
+        let mut total = 0;
+
+        for d in ALL_DIGITS {
+            let mut needed = 0;
+            let mut available = 0;
+            for i in 0..8 {
+                if candidates[i].0 & (1 << d) != 0 {
+                    needed |= 1 << i;
+                    if candidates[(i + 1) % 8].0 & (1 << d) != 0 {
+                        available |= 1 << i;
+                    }
+                }
+            }
+
+            if needed == 0 {
+                continue;
+            }
+
+            // Try every subset of the available links, keeping the smallest that covers every pair containing the digit.
+            let mut best: Option<u16> = None;
+            let mut subset: u16 = available;
+            loop {
+                let covered = subset | subset.rotate_left(1) & 0xff | subset >> 7;
+                let smaller = match best {
+                    None => true,
+                    Some(b) => count(subset) < count(b),
+                };
+                if covered & needed == needed && smaller {
+                    best = Some(subset);
+                }
+                if subset == 0 {
+                    break;
+                }
+                subset = (subset - 1) & available;
+            }
+
+            let best = best?;
+            for (i, link) in links.iter_mut().enumerate() {
+                if best & (1 << i) != 0 {
+                    link.0 |= 1 << d;
+                }
+            }
+            total += count(best);
+        }
+
+        if total == 16 {
+            Some(links)
+        } else {
+            None
+        }
+    }
+
+    // sk_loop_pair returns the other 2 cells in the box of the corner that are on the corner's row (if `row` is set) or column.
+    fn sk_loop_pair(r: usize, c: usize, row: bool) -> [Point; 2] {
+        if row {
+            let cs: Vec<usize> = (c / 3 * 3..c / 3 * 3 + 3).filter(|&x| x != c).collect();
+            [(r, cs[0]), (r, cs[1])]
+        } else {
+            let rs: Vec<usize> = (r / 3 * 3..r / 3 * 3 + 3).filter(|&x| x != r).collect();
+            [(rs[0], c), (rs[1], c)]
+        }
+    }
+}
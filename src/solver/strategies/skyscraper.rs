@@ -0,0 +1,122 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{box_of, count, Cell, Grid, Group, Point, ALL_DIGITS, COL, COLS, ROW, ROWS};
+use super::neighbors;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // skyscraper removes candidates. If 2 columns (or rows) each have only two places for a digit and one place of each is in the same row (or column), then one of the other two places must be the digit, so it can be removed from any cell that sees both of them. It returns true if it changes any cells.
+    pub fn skyscraper(&mut self) -> bool {
+        self.skyscraper_group(&COL, &ROW) || self.skyscraper_group(&ROW, &COL)
+    }
+
+    // two_string_kite removes candidates. If a row and a column each have only two places for a digit and one place from each is in the same box (but not the same cell), then one of the other two places must be the digit, so it can be removed from the cell in the row of the column's other place and the column of the row's other place. It returns true if it changes any cells.
+    pub fn two_string_kite(&mut self) -> bool {
+        let mut res = false;
+        let rows = self.group_places(&ROW);
+        let cols = self.group_places(&COL);
+
+        for d in ALL_DIGITS {
+            for (r, row) in rows.iter().enumerate() {
+                if count(row[d]) != 2 {
+                    continue;
+                }
+
+                for (c, col) in cols.iter().enumerate() {
+                    if count(col[d]) != 2 {
+                        continue;
+                    }
+
+                    let row_ends: Vec<usize> = (0..9).filter(|&x| row[d] & (1 << x) != 0).collect();
+                    let col_ends: Vec<usize> = (0..9).filter(|&x| col[d] & (1 << x) != 0).collect();
+
+                    for (i, j) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+                        let (a, b) = ((r, row_ends[i]), (col_ends[j], c));
+                        let (row_end, col_end) = ((r, row_ends[1 - i]), (col_ends[1 - j], c));
+                        let target = (col_end.0, row_end.1);
+                        if a == b || box_of(a.0, a.1) != box_of(b.0, b.1) || row_end == b || col_end == a || target == row_end || target == col_end {
+                            continue;
+                        }
+
+                        if self[&target].and_not(&Cell(1 << d)) {
+                            cell_change!(
+                                self,
+                                res,
+                                &[a, row_end, b, col_end],
+                                Cell(1 << d),
+                                "two-string kite on row {} and col {} joined in box {}, removing {} from {:?}",
+                                r,
+                                c,
+                                box_of(a.0, a.1),
+                                d,
+                                target
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // skyscraper_group finds pairs of units in the major group that each have two places for a digit, sharing exactly one unit of the minor group.
+    fn skyscraper_group(&mut self, major_group: &Group, minor_group: &Group) -> bool {
+        let mut res = false;
+        let places = self.group_places(major_group);
+
+        for d in ALL_DIGITS {
+            for u1 in 0..8 {
+                for u2 in u1 + 1..9 {
+                    let (p1, p2) = (places[u1][d], places[u2][d]);
+                    if count(p1) != 2 || count(p2) != 2 || count(p1 & p2) != 1 {
+                        continue;
+                    }
+
+                    let top = |ui: usize, p: u16| -> Point { major_group.cells[ui][(p & !(p1 & p2)).trailing_zeros() as usize] };
+                    let (t1, t2) = (top(u1, p1), top(u2, p2));
+                    let (n1, n2) = (neighbors(&t1), neighbors(&t2));
+                    let base = (p1 & p2).trailing_zeros() as usize;
+                    let pattern = [major_group.cells[u1][base], t1, major_group.cells[u2][base], t2];
+
+                    for_all_cells!(r, c, {
+                        if n1[r][c] && n2[r][c] && self.cells[r][c].and_not(&Cell(1 << d)) {
+                            cell_change!(
+                                self,
+                                res,
+                                &pattern,
+                                Cell(1 << d),
+                                "skyscraper on {}s {} and {} with base {} {}, removing {} from ({}, {})",
+                                major_group.name,
+                                u1,
+                                u2,
+                                minor_group.name,
+                                base,
+                                d,
+                                r,
+                                c
+                            );
+                        }
+                    });
+                }
+            }
+        }
+
+        res
+    }
+}
@@ -0,0 +1,97 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{count, Cell, Grid, Group, Point, ALL_DIGITS, BOX, COL, ROW};
+use super::combinations;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // naked_pair checks a group for 2 cells containing only the same pair of values. If present, those values can be removed from all other cells in the group. It returns true if it changes any cells.
+    pub fn naked_pair(&mut self) -> bool {
+        self.subset(2, true)
+    }
+
+    // naked_triple checks a group for 3 cells whose candidates together are only 3 digits (each cell need not have all 3). If present, those digits can be removed from all other cells in the group. It returns true if it changes any cells.
+    pub fn naked_triple(&mut self) -> bool {
+        self.subset(3, true)
+    }
+
+    // naked_quad checks a group for 4 cells whose candidates together are only 4 digits (each cell need not have all 4). If present, those digits can be removed from all other cells in the group. It returns true if it changes any cells.
+    pub fn naked_quad(&mut self) -> bool {
+        self.subset(4, true)
+    }
+
+    // hidden_pair removes other digits from a pair of cells in a group (box, column, row) when that pair contains the only occurrances of the digits in the group. It returns true if it changes any cells.
+    pub fn hidden_pair(&mut self) -> bool {
+        self.subset(2, false)
+    }
+
+    // hidden_triple removes other digits from a triple of cells in a group (box, column, row) when that triple contains the only occurrances of the digits in the group. It returns true if it changes any cells.
+    pub fn hidden_triple(&mut self) -> bool {
+        self.subset(3, false)
+    }
+
+    // hidden_quad removes other digits from a quad of cells in a group (box, column, row) when that quad contains the only occurrances of the digits in the group. It returns true if it changes any cells.
+    pub fn hidden_quad(&mut self) -> bool {
+        self.subset(4, false)
+    }
+
+    // subset finds naked (`size` cells containing only `size` digits) or hidden (`size` digits appearing only in `size` cells) subsets in the boxes, columns and rows.
+    fn subset(&mut self, size: usize, naked: bool) -> bool {
+        self.subset_group(&BOX, size, naked) || self.subset_group(&COL, size, naked) || self.subset_group(&ROW, size, naked)
+    }
+
+    fn subset_group(&mut self, group: &Group, size: usize, naked: bool) -> bool {
+        group_loop!(self, res, group, ci, c, {
+            if naked {
+                let cells: Vec<usize> = (0..9).filter(|&pi| (2..=size).contains(&self[&c[pi]].count())).collect();
+
+                for subset in combinations(&cells, size) {
+                    let comb = subset.iter().fold(Cell(0), |acc, &pi| acc | self[&c[pi]]);
+                    if comb.count() != size {
+                        continue;
+                    }
+
+                    let pattern: Vec<Point> = subset.iter().map(|&pi| c[pi]).collect();
+                    for (pi, p) in c.iter().enumerate() {
+                        if !subset.contains(&pi) && self[p].and_not(&comb) {
+                            cell_change!(self, res, &pattern, comb, "in {} {} {:?} removing {} from {:?}", group.name, ci, pattern, comb.to_string(), p);
+                        }
+                    }
+                }
+            } else {
+                let places = self.digit_places(c);
+                let digits: Vec<usize> = ALL_DIGITS.filter(|&d| (2..=size).contains(&(count(places[d]) as usize))).collect();
+
+                for subset in combinations(&digits, size) {
+                    let comb = subset.iter().fold(0, |acc, &d| acc | places[d]);
+                    if count(comb) as usize != size {
+                        continue;
+                    }
+
+                    let bits = Cell(subset.iter().fold(0, |acc, &d| acc | 1 << d));
+                    let pattern: Vec<Point> = (0..9).filter(|&pi| comb & (1 << pi) != 0).map(|pi| c[pi]).collect();
+                    for (pi, p) in c.iter().enumerate() {
+                        if comb & (1 << pi) != 0 && self[p].and(&bits) {
+                            cell_change!(self, res, &pattern, bits, "in {} {} limits {:?} to {}", group.name, ci, p, bits.to_string());
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
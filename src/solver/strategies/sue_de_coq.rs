@@ -0,0 +1,99 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, Point, COL, ROW};
+use super::{combinations, intersections};
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // sue_de_coq removes candidates. Take 2 or 3 cells in the intersection of a box and a row or column that together contain at least 2 more digits than cells, some cells from the rest of the line, and some cells from the rest of the box, where no digit is in both the line cells and the box cells. If all of these cells together contain exactly as many digits as cells, each digit must appear in them exactly once. A digit that is not in the box cells is then locked in the line, and a digit that is not in the line cells is locked in the box, so those digits can be removed from the rest of the line or box. The cells of the intersection that are not used see every cell of the pattern, so all of its digits can be removed from them. It returns true if it changes any cells.
+    pub fn sue_de_coq(&mut self) -> bool {
+        let mut res = false;
+
+        for line in [&*COL, &*ROW] {
+            for i in intersections(line) {
+                let open = |cells: &[Point]| -> Vec<Point> { cells.iter().filter(|p| self[p].count() > 1).copied().collect() };
+                let (middle, line_rest, box_rest) = (open(&i.middle), open(&i.line_rest), open(&i.box_rest));
+
+                if self.sue_de_coq_intersection(&middle, &line_rest, &box_rest, &format!("box {} and {} {}", i.box_index, line.name, i.line_index)) {
+                    res = true;
+                }
+            }
+        }
+
+        res
+    }
+
+    // sue_de_coq_intersection tries every choice of cells in the intersection (middle) and in the rest of the line and box.
+    fn sue_de_coq_intersection(&mut self, middle: &[Point], line_rest: &[Point], box_rest: &[Point], name: &str) -> bool {
+        let mut res = false;
+        let union = |grid: &Grid, all: &[Point], chosen: &[usize]| chosen.iter().fold(Cell(0), |acc, &i| acc | grid[&all[i]]);
+        let line_indexes: Vec<usize> = (0..line_rest.len()).collect();
+        let box_indexes: Vec<usize> = (0..box_rest.len()).collect();
+
+        for size in 2..=middle.len() {
+            for m in combinations(&(0..middle.len()).collect::<Vec<usize>>(), size) {
+                let middle_digits = union(self, middle, &m);
+                if middle_digits.count() < size + 2 {
+                    continue;
+                }
+
+                for line_size in 1..=line_rest.len().min(3) {
+                    for a in combinations(&line_indexes, line_size) {
+                        let line_digits = union(self, line_rest, &a);
+
+                        for box_size in 1..=box_rest.len().min(3) {
+                            for b in combinations(&box_indexes, box_size) {
+                                let box_digits = union(self, box_rest, &b);
+                                let all = middle_digits | line_digits | box_digits;
+                                if (line_digits & box_digits).count() != 0 || all.count() != size + line_size + box_size {
+                                    continue;
+                                }
+
+                                let cells: Vec<Point> = m.iter().map(|&i| middle[i]).chain(a.iter().map(|&i| line_rest[i])).chain(b.iter().map(|&i| box_rest[i])).collect();
+                                let line_locked = all & !box_digits;
+                                let box_locked = all & !line_digits;
+
+                                for (i, p) in middle.iter().enumerate() {
+                                    if !m.contains(&i) && self[p].and_not(&all) {
+                                        cell_change!(self, res, &cells, all, "sue de coq in {} with {:?} removes {} from {:?}", name, cells, all, p);
+                                    }
+                                }
+                                for (i, p) in line_rest.iter().enumerate() {
+                                    if !a.contains(&i) && self[p].and_not(&line_locked) {
+                                        cell_change!(self, res, &cells, all, "sue de coq in {} with {:?} removes {} from {:?}", name, cells, line_locked, p);
+                                    }
+                                }
+                                for (i, p) in box_rest.iter().enumerate() {
+                                    if !b.contains(&i) && self[p].and_not(&box_locked) {
+                                        cell_change!(self, res, &cells, all, "sue de coq in {} with {:?} removes {} from {:?}", name, cells, box_locked, p);
+                                    }
+                                }
+
+                                if res {
+                                    return res;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
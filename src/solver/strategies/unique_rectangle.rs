@@ -0,0 +1,479 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{box_of, Cell, Grid, Point, Unit, BOX, COL, COLS, ROW, ROWS};
+use super::{combinations, neighbors};
+use log::Level;
+use log::{info, log_enabled};
+
+// A rectangle is four cells in two rows, two columns and two boxes that all contain the same pair of digits. The corners are ordered (r1, c1), (r1, c2), (r2, c1), (r2, c2), so the corner diagonally opposite corner i is corner 3 - i. A puzzle with a single solution cannot end with the pair in all four corners, since the two digits could then be swapped.
+struct Rectangle {
+    corners: [Point; 4],
+    pair: Cell,
+}
+
+impl Rectangle {
+    // valid checks that the rectangle has not been broken by changes made since it was found: every corner must still contain the pair.
+    fn valid(&self, grid: &Grid) -> bool {
+        self.corners.iter().all(|p| grid[p] & self.pair == self.pair)
+    }
+
+    // floor returns the indexes of the corners that contain only the pair.
+    fn floor(&self, grid: &Grid) -> Vec<usize> {
+        (0..4).filter(|&i| grid[&self.corners[i]] == self.pair).collect()
+    }
+
+    // roof returns the indexes of the corners that contain other digits in addition to the pair.
+    fn roof(&self, grid: &Grid) -> Vec<usize> {
+        (0..4).filter(|&i| grid[&self.corners[i]] != self.pair).collect()
+    }
+
+    // shared_units returns the units (row or column, and box) containing both corners.
+    fn shared_units(&self, i: usize, j: usize) -> Vec<&'static Unit> {
+        let (p1, p2) = (self.corners[i], self.corners[j]);
+        let mut units = Vec::new();
+        if p1.0 == p2.0 {
+            units.push(&ROW.cells[p1.0]);
+        }
+        if p1.1 == p2.1 {
+            units.push(&COL.cells[p1.1]);
+        }
+        if box_of(p1.0, p1.1) == box_of(p2.0, p2.1) {
+            units.push(&BOX.cells[box_of(p1.0, p1.1)]);
+        }
+        units
+    }
+}
+
+impl Grid {
+    // unique_rectangle_1 removes candidates. If three corners of a rectangle contain only the pair, the fourth corner cannot be either digit of the pair. It returns true if it changes any cells.
+    pub fn unique_rectangle_1(&mut self) -> bool {
+        let mut res = false;
+
+        for rect in self.unique_rectangles() {
+            if !rect.valid(self) {
+                continue;
+            }
+
+            let roof = rect.roof(self);
+            if roof.len() != 1 {
+                continue;
+            }
+
+            let p = rect.corners[roof[0]];
+            if self[&p].and_not(&rect.pair) {
+                cell_change!(self, res, &rect.corners, rect.pair, "unique rectangle type 1 {:?} removes {} from {:?}", rect.corners, rect.pair, p);
+            }
+        }
+
+        res
+    }
+
+    // unique_rectangle_2 removes candidates. If two corners in the same row or column contain the pair and the same single extra digit, one of them must be the extra digit, so it can be removed from any cell that sees both of them. It returns true if it changes any cells.
+    pub fn unique_rectangle_2(&mut self) -> bool {
+        self.unique_rectangle_extra(false)
+    }
+
+    // unique_rectangle_3 removes candidates. If two corners in the same unit contain extra digits, one of them must be an extra digit, so together they act as a single cell containing only the extra digits. If that cell and other cells in the unit form a naked subset, the digits of the subset can be removed from the rest of the unit. It returns true if it changes any cells.
+    pub fn unique_rectangle_3(&mut self) -> bool {
+        let mut res = false;
+
+        for rect in self.unique_rectangles() {
+            if !rect.valid(self) {
+                continue;
+            }
+
+            let roof = rect.roof(self);
+            if roof.len() != 2 {
+                continue;
+            }
+
+            let (p1, p2) = (rect.corners[roof[0]], rect.corners[roof[1]]);
+            let extra = (self[&p1] | self[&p2]) & !rect.pair;
+            if extra.count() < 2 {
+                continue;
+            }
+
+            for unit in rect.shared_units(roof[0], roof[1]) {
+                let others: Vec<usize> = (0..9).filter(|&i| unit[i] != p1 && unit[i] != p2 && self[&unit[i]].count() > 1).collect();
+
+                for size in extra.count()..=4 {
+                    for subset in combinations(&others, size - 1) {
+                        let digits = subset.iter().fold(extra, |acc, &i| acc | self[&unit[i]]);
+                        if digits.count() != size {
+                            continue;
+                        }
+
+                        let pattern: Vec<Point> = rect.corners.iter().copied().chain(subset.iter().map(|&i| unit[i])).collect();
+                        for (i, p) in unit.iter().enumerate() {
+                            if *p == p1 || *p == p2 || subset.contains(&i) {
+                                continue;
+                            }
+
+                            if self[p].and_not(&digits) {
+                                cell_change!(
+                                    self,
+                                    res,
+                                    &pattern,
+                                    rect.pair | digits,
+                                    "unique rectangle type 3 {:?} with {:?} removes {} from {:?}",
+                                    rect.corners,
+                                    &pattern[4..],
+                                    digits,
+                                    p
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // unique_rectangle_4 removes candidates. If two corners in the same unit contain extra digits and one digit of the pair appears nowhere else in that unit, one of the two corners must be that digit and the other cannot be the other digit of the pair, so the other digit of the pair can be removed from both corners. It returns true if it changes any cells.
+    pub fn unique_rectangle_4(&mut self) -> bool {
+        let mut res = false;
+
+        for rect in self.unique_rectangles() {
+            if !rect.valid(self) {
+                continue;
+            }
+
+            let roof = rect.roof(self);
+            if roof.len() != 2 {
+                continue;
+            }
+
+            let (p1, p2) = (rect.corners[roof[0]], rect.corners[roof[1]]);
+            for unit in rect.shared_units(roof[0], roof[1]) {
+                let points = self.digit_points(unit);
+
+                for d in rect.pair.digits() {
+                    if points[d].len() != 2 || !points[d].contains(&p1) || !points[d].contains(&p2) {
+                        continue;
+                    }
+
+                    let other = Cell(rect.pair.0 & !(1 << d));
+                    for p in &[p1, p2] {
+                        if self[p].and_not(&other) {
+                            cell_change!(
+                                self,
+                                res,
+                                &rect.corners,
+                                rect.pair,
+                                "unique rectangle type 4 {:?} locks {} in the roof, removing {} from {:?}",
+                                rect.corners,
+                                d,
+                                other,
+                                p
+                            );
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+
+        res
+    }
+
+    // unique_rectangle_5 removes candidates. If two diagonal corners, or three corners, contain the pair and the same single extra digit, one of them must be the extra digit, so it can be removed from any cell that sees all of them. It returns true if it changes any cells.
+    pub fn unique_rectangle_5(&mut self) -> bool {
+        self.unique_rectangle_extra(true)
+    }
+
+    // unique_rectangle_6 removes candidates. If two diagonal corners contain only the pair and one digit of the pair appears in both rows (or both columns) only in the corners, that digit must be in the two corners that contain only the pair, so it can be removed from the other two corners. It returns true if it changes any cells.
+    pub fn unique_rectangle_6(&mut self) -> bool {
+        let mut res = false;
+
+        for rect in self.unique_rectangles() {
+            if !rect.valid(self) {
+                continue;
+            }
+
+            let floor = rect.floor(self);
+            if floor.len() != 2 || floor[0] + floor[1] != 3 {
+                continue;
+            }
+
+            let (p0, p3) = (rect.corners[0], rect.corners[3]);
+            let rows = [&ROW.cells[p0.0], &ROW.cells[p3.0]];
+            let cols = [&COL.cells[p0.1], &COL.cells[p3.1]];
+
+            for d in rect.pair.digits() {
+                let locked = |units: &[&Unit; 2]| units.iter().all(|u| self.digit_points(u)[d].iter().all(|p| rect.corners.contains(p)));
+                if !locked(&rows) && !locked(&cols) {
+                    continue;
+                }
+
+                for i in rect.roof(self) {
+                    let p = rect.corners[i];
+                    if self[&p].and_not(&Cell(1 << d)) {
+                        cell_change!(
+                            self,
+                            res,
+                            &rect.corners,
+                            rect.pair,
+                            "unique rectangle type 6 {:?} locks {} in the rows or columns, removing from {:?}",
+                            rect.corners,
+                            d,
+                            p
+                        );
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // hidden_unique_rectangle removes candidates. If a corner contains only the pair and, in the row and column of the diagonally opposite corner, one digit of the pair appears only in the corners, then the opposite corner cannot be the other digit of the pair; otherwise all four corners would have to be the pair. It returns true if it changes any cells.
+    pub fn hidden_unique_rectangle(&mut self) -> bool {
+        let mut res = false;
+
+        for rect in self.unique_rectangles() {
+            if !rect.valid(self) {
+                continue;
+            }
+
+            for f in rect.floor(self) {
+                let p = rect.corners[3 - f];
+                if self[&p] == rect.pair {
+                    continue;
+                }
+
+                for d in rect.pair.digits() {
+                    let locked = |unit: &Unit| self.digit_points(unit)[d].iter().all(|q| rect.corners.contains(q));
+                    if !locked(&ROW.cells[p.0]) || !locked(&COL.cells[p.1]) {
+                        continue;
+                    }
+
+                    let other = Cell(rect.pair.0 & !(1 << d));
+                    if self[&p].and_not(&other) {
+                        cell_change!(
+                            self,
+                            res,
+                            &rect.corners,
+                            rect.pair,
+                            "hidden unique rectangle {:?} locks {}, removing {} from {:?}",
+                            rect.corners,
+                            d,
+                            other,
+                            p
+                        );
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    // unique_rectangle_extra handles types 2 and 5: every roof corner contains the pair and the same single extra digit, so the extra digit can be removed from the cells that see all of the roof corners. `diagonal` selects type 5 (roof corners that are not all in one row or column) instead of type 2.
+    fn unique_rectangle_extra(&mut self, diagonal: bool) -> bool {
+        let mut res = false;
+
+        for rect in self.unique_rectangles() {
+            if !rect.valid(self) {
+                continue;
+            }
+
+            let roof = rect.roof(self);
+            if roof.len() < 2 {
+                continue;
+            }
+
+            let first = self[&rect.corners[roof[0]]];
+            if first.count() != 3 || roof.iter().any(|&i| self[&rect.corners[i]] != first) {
+                continue;
+            }
+
+            let aligned = roof.len() == 2 && roof[0] + roof[1] != 3;
+            if aligned == diagonal {
+                continue;
+            }
+
+            let extra = first & !rect.pair;
+            let ns: Vec<[[bool; COLS]; ROWS]> = roof.iter().map(|&i| neighbors(&rect.corners[i])).collect();
+            for_all_cells!(r, c, {
+                if ns.iter().all(|n| n[r][c]) && self.cells[r][c].and_not(&extra) {
+                    cell_change!(
+                        self,
+                        res,
+                        &rect.corners,
+                        first,
+                        "unique rectangle type {} {:?} removes {} from ({}, {})",
+                        if diagonal { 5 } else { 2 },
+                        rect.corners,
+                        extra,
+                        r,
+                        c
+                    );
+                }
+            });
+        }
+
+        res
+    }
+
+    // unique_rectangles finds all of the rectangles where at least one corner contains only the pair, if the puzzle is assumed to have a single solution.
+    fn unique_rectangles(&self) -> Vec<Rectangle> {
+        let mut rects = Vec::new();
+        if !self.assume_unique {
+            return rects;
+        }
+
+        for r1 in 0..8 {
+            for r2 in r1 + 1..9 {
+                for c1 in 0..8 {
+                    for c2 in c1 + 1..9 {
+                        // The corners must be in exactly two boxes.
+                        if (r1 / 3 == r2 / 3) == (c1 / 3 == c2 / 3) {
+                            continue;
+                        }
+
+                        let corners = [(r1, c1), (r1, c2), (r2, c1), (r2, c2)];
+                        let common = corners.iter().fold(Cell(!0), |acc, p| acc & self[p]);
+                        if common.count() < 2 {
+                            continue;
+                        }
+
+                        for pair in combinations(&common.digits(), 2) {
+                            let pair = Cell(1 << pair[0] | 1 << pair[1]);
+                            if corners.iter().any(|p| self[p] == pair) {
+                                rects.push(Rectangle { corners, pair });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        rects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::marked;
+    use super::*;
+
+    // The rectangles below have corners (0, 0), (0, 3), (1, 0) and (1, 3) in boxes 0 and 1, on the pair 1 and 2.
+    fn unique(marks: &[(Point, &str)]) -> Grid {
+        let mut grid = marked(marks);
+        grid.assume_unique = true;
+        grid
+    }
+
+    // others lists the cells of a row or column, other than the corners, with the given candidates.
+    fn others(cells: impl Iterator<Item = Point>, candidates: &'static str) -> Vec<(Point, &'static str)> {
+        cells.filter(|p| ![(0, 0), (0, 3), (1, 0), (1, 3)].contains(p)).map(|p| (p, candidates)).collect()
+    }
+
+    #[test]
+    fn type_1() {
+        let mut grid = unique(&[((0, 0), "12"), ((0, 3), "12"), ((1, 0), "12"), ((1, 3), "123")]);
+        assert!(grid.unique_rectangle_1());
+        assert_eq!(grid.cells[1][3].digits(), vec![3]);
+    }
+
+    #[test]
+    fn type_1_needs_assume_unique() {
+        let mut grid = marked(&[((0, 0), "12"), ((0, 3), "12"), ((1, 0), "12"), ((1, 3), "123")]);
+        let before = grid.encode_candidates();
+        assert!(!grid.unique_rectangle_1());
+        assert!(!grid.unique_rectangle_2() && !grid.unique_rectangle_3() && !grid.unique_rectangle_4());
+        assert!(!grid.unique_rectangle_5() && !grid.unique_rectangle_6() && !grid.hidden_unique_rectangle());
+        assert_eq!(grid.encode_candidates(), before);
+    }
+
+    #[test]
+    fn type_2() {
+        let mut grid = unique(&[((0, 0), "12"), ((0, 3), "123"), ((1, 0), "12"), ((1, 3), "123")]);
+        assert!(grid.unique_rectangle_2());
+        // The cells that see both roof corners, in column 3 and box 1, lose the 3.
+        assert_eq!(grid.cells[5][3].digits(), vec![1, 2, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[2][4].digits(), vec![1, 2, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[0][6].digits().len(), 9);
+        assert_eq!(grid.cells[0][3].digits(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn type_3() {
+        let mut grid = unique(&[((0, 0), "12"), ((0, 3), "123"), ((1, 0), "12"), ((1, 3), "124"), ((5, 3), "34")]);
+        assert!(grid.unique_rectangle_3());
+        // The roof acts as a cell containing 3 and 4, which with (5, 3) is a naked pair in column 3.
+        assert_eq!(grid.cells[8][3].digits(), vec![1, 2, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[5][3].digits(), vec![3, 4]);
+        assert_eq!(grid.cells[2][4].digits().len(), 9);
+    }
+
+    #[test]
+    fn type_4() {
+        let mut marks = vec![((0, 0), "12"), ((0, 3), "123"), ((1, 0), "12"), ((1, 3), "124")];
+        marks.extend(others((0..9).map(|r| (r, 3)), "23456789"));
+        let mut grid = unique(&marks);
+        assert!(grid.unique_rectangle_4());
+        // One of the roof corners is the 1, so neither can be the 2.
+        assert_eq!(grid.cells[0][3].digits(), vec![1, 3]);
+        assert_eq!(grid.cells[1][3].digits(), vec![1, 4]);
+    }
+
+    #[test]
+    fn type_5() {
+        let mut grid = unique(&[((0, 0), "12"), ((0, 3), "123"), ((1, 0), "123"), ((1, 3), "12")]);
+        assert!(grid.unique_rectangle_5());
+        // The diagonal roof corners are seen together from row 0 and box 0, and from row 1 and box 1.
+        assert_eq!(grid.cells[0][1].digits(), vec![1, 2, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[1][5].digits(), vec![1, 2, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[2][0].digits().len(), 9);
+        assert!(!grid.unique_rectangle_2());
+    }
+
+    #[test]
+    fn type_5_three_corners() {
+        let mut grid = unique(&[((0, 0), "12"), ((0, 3), "123"), ((1, 0), "123"), ((1, 3), "123")]);
+        assert!(grid.unique_rectangle_5());
+        // Only the other cells of row 1 in box 1 see all three roof corners.
+        assert_eq!(grid.cells[1][4].digits(), vec![1, 2, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(grid.cells[0][4].digits().len(), 9);
+    }
+
+    #[test]
+    fn type_6() {
+        let mut marks = vec![((0, 0), "12"), ((0, 3), "123"), ((1, 0), "124"), ((1, 3), "12")];
+        marks.extend(others((0..9).map(|c| (0, c)), "23456789"));
+        marks.extend(others((0..9).map(|c| (1, c)), "23456789"));
+        let mut grid = unique(&marks);
+        assert!(grid.unique_rectangle_6());
+        // The 1s of rows 0 and 1 must be on a diagonal, and the floor diagonal is the only one left.
+        assert_eq!(grid.cells[0][3].digits(), vec![2, 3]);
+        assert_eq!(grid.cells[1][0].digits(), vec![2, 4]);
+    }
+
+    #[test]
+    fn hidden() {
+        let mut marks = vec![((0, 0), "12"), ((0, 3), "123"), ((1, 0), "124"), ((1, 3), "1235")];
+        marks.extend(others((0..9).map(|c| (1, c)), "23456789"));
+        marks.extend(others((0..9).map(|r| (r, 3)), "23456789"));
+        let mut grid = unique(&marks);
+        assert!(grid.hidden_unique_rectangle());
+        // If (1, 3) were the 2, the 1s of row 1 and column 3 would fill the rest of the rectangle.
+        assert_eq!(grid.cells[1][3].digits(), vec![1, 3, 5]);
+    }
+}
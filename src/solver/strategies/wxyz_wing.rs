@@ -0,0 +1,82 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Cell, Grid, Point, COLS, ROWS};
+use super::neighbors;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // wxyz_wing removes candidates. If four cells contain only four digits (WXYZ) between them and every digit except Z is restricted (all of the cells containing it can "see" each other), then one of the cells containing Z must be Z; otherwise the four cells would have to be filled from only three digits, each used at most once. Any cell that can see all of the cells containing Z can have Z removed. It returns true if it changes any cells.
+    pub fn wxyz_wing(&mut self) -> bool {
+        let mut points = Vec::<Point>::new();
+        for_all_cells!(r, c, {
+            let count = self.cells[r][c].count();
+            if count > 1 && count <= 4 {
+                points.push((r, c));
+            }
+        });
+
+        let ns: Vec<[[bool; COLS]; ROWS]> = points.iter().map(neighbors).collect();
+        self.wxyz_wing_set(&points, &ns, &mut Vec::new(), 0, Cell(0), Cell(0))
+    }
+
+    // wxyz_wing_set extends the set of cells by one cell at a time, stopping as soon as the cells contain more than four digits or more than one digit is not restricted.
+    fn wxyz_wing_set(&mut self, points: &[Point], ns: &[[[bool; COLS]; ROWS]], set: &mut Vec<usize>, start: usize, union: Cell, broken: Cell) -> bool {
+        let mut res = false;
+
+        if set.len() == 4 {
+            if union.count() != 4 || broken.count() != 1 {
+                return false;
+            }
+
+            let holders: Vec<usize> = set.iter().copied().filter(|&i| (self[&points[i]] & broken) != Cell(0)).collect();
+            for_all_cells!(r, c, {
+                if holders.iter().all(|&i| ns[i][r][c]) && self.cells[r][c].and_not(&broken) {
+                    let wing: Vec<Point> = set.iter().map(|&i| points[i]).collect();
+                    cell_change!(self, res, &wing, union, "{:?} causes clearing of {} from ({}, {})", wing, broken, r, c);
+                }
+            });
+
+            return res;
+        }
+
+        for i in start..points.len() {
+            let cell = self[&points[i]];
+            let u = union | cell;
+            if u.count() > 4 {
+                continue;
+            }
+
+            // A digit is no longer restricted if it appears in this cell and in a cell of the set that this cell cannot see.
+            let mut b = broken;
+            for &j in set.iter() {
+                if !ns[i][points[j].0][points[j].1] {
+                    b = b | (cell & self[&points[j]]);
+                }
+            }
+            if b.count() > 1 {
+                continue;
+            }
+
+            set.push(i);
+            res |= self.wxyz_wing_set(points, ns, set, i + 1, u, b);
+            set.pop();
+        }
+
+        res
+    }
+}
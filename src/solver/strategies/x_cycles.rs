@@ -0,0 +1,40 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Grid, ALL_DIGITS};
+use super::aic::Rules;
+
+impl Grid {
+    // x_cycles removes candidates. For a single digit, a strong link joins two cells that are the only places for the digit in a unit (if one is not the digit, the other is) and a weak link joins any two cells in a unit (if one is the digit, the other is not). In a loop of alternating strong and weak links, one cell of every weak link must be the digit, so it can be removed from any other cell that sees both cells of a weak link. If the loop is broken at one cell by two strong links, that cell must be the digit; if it is broken by two weak links, that cell cannot be the digit. These are alternating inference chains restricted to one digit and single cells. Cycles are limited to max_chain strong links. It returns true if it changes any cells.
+    pub fn x_cycles(&mut self) -> bool {
+        for d in ALL_DIGITS {
+            let rules = Rules {
+                digit: Some(d),
+                bi_location: true,
+                bi_value: false,
+                grouped: false,
+                cell_weak: false,
+            };
+
+            // Each strong link joins two nodes.
+            if self.chain("x-cycle", rules, self.max_chain * 2) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
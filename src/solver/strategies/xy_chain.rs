@@ -0,0 +1,34 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::Grid;
+use super::aic::Rules;
+
+impl Grid {
+    // xy_chain removes candidates. A chain of cells with two candidates each, where each cell can "see" the next and shares a digit with it, forces the digits along the chain: if the first cell is not Z, it is its other digit, so the next cell is not that digit and must be its other digit, and so on. If the last cell would then have to be Z, one of the ends of the chain must be Z, so Z can be removed from any cell that can see both ends. y_wing is an xy_chain of 3 cells. These are alternating inference chains whose strong links are all within cells. Chains are limited to max_chain cells. It returns true if it changes any cells.
+    pub fn xy_chain(&mut self) -> bool {
+        let rules = Rules {
+            digit: None,
+            bi_location: false,
+            bi_value: true,
+            grouped: false,
+            cell_weak: false,
+        };
+
+        // Each cell of the chain is two nodes, joined by a strong link.
+        self.chain("xy-chain", rules, self.max_chain * 2)
+    }
+}
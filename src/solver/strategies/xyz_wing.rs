@@ -0,0 +1,66 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::super::{Grid, BOX, COLS, ROWS};
+use super::neighbors;
+use log::Level;
+use log::{info, log_enabled};
+
+impl Grid {
+    // xyz_wing removes candidates. If a cell has three candidates (XYZ) and it can "see" a cell containing XZ and another cell containing YZ, then one of the three cells must be Z, so any cell that can be seen by all three that contains Z can have Z removed. It returns true if it changes any cells.
+    pub fn xyz_wing(&mut self) -> bool {
+        let mut res = false;
+
+        // Traverse all cells, using box units for convenience.
+        for u in &BOX.cells {
+            for p in u {
+                let cell = self[p];
+
+                if cell.count() != 3 {
+                    continue;
+                }
+
+                let clone = *self;
+                let candidates = clone.find_y_wing_candidates(p, 2);
+                let n = neighbors(p);
+
+                for (c1i, p1) in candidates.iter().enumerate() {
+                    let cell1 = self[p1];
+                    let n1 = neighbors(p1);
+
+                    for p2 in candidates[c1i + 1..].iter() {
+                        let cell2 = self[p2];
+
+                        if cell1 == cell2 {
+                            continue;
+                        }
+
+                        let n2 = neighbors(p2);
+                        let bits = cell1 & cell2;
+
+                        for_all_cells!(r, c, {
+                            if n[r][c] && n1[r][c] && n2[r][c] && self.cells[r][c].and_not(&bits) {
+                                cell_change!(self, res, &[*p, **p1, **p2], cell, "{:?}, {:?}, {:?} causes clearing of {} from ({}, {})", p, p1, p2, bits, r, c);
+                            }
+                        });
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
@@ -28,31 +28,31 @@ impl Grid {
         // Traverse all cells, using box units for convenience.
         for u in &BOX.cells {
             for p in u {
-                let cell = self[&p];
+                let cell = self[p];
 
                 if cell.count() != 2 {
                     continue;
                 }
 
-                let clone = self.clone();
+                let clone = *self;
                 let candidates = clone.find_y_wing_candidates(p, 1);
 
                 for (c1i, p1) in candidates.iter().enumerate() {
-                    let cell1 = self[&p1];
-                    let n1 = neighbors(&p1);
+                    let cell1 = self[p1];
+                    let n1 = neighbors(p1);
 
                     for (c2i, p2) in candidates.iter().enumerate() {
                         if c1i == c2i {
                             continue;
                         }
 
-                        let cell2 = self[&p2];
+                        let cell2 = self[p2];
 
                         if (cell1 | cell2).count() != 3 || (cell & cell1 | cell & cell2) != cell {
                             continue;
                         }
 
-                        let n2 = neighbors(&p2);
+                        let n2 = neighbors(p2);
 
                         let mut overlap = [[false; COLS]; ROWS];
                         for_all_cells!(r, c, {
@@ -65,7 +65,19 @@ impl Grid {
                             if overlap[r][c] {
                                 let bits = (cell1 | cell2) & !cell;
                                 if self.cells[r][c].and_not(&bits) {
-                                    cell_change!(self, res, "{:?}, {:?}, {:?} causes clearing of {} from ({}, {})", p, p1, p2, bits, r, c);
+                                    cell_change!(
+                                        self,
+                                        res,
+                                        &[*p, **p1, **p2],
+                                        cell | cell1 | cell2,
+                                        "{:?}, {:?}, {:?} causes clearing of {} from ({}, {})",
+                                        p,
+                                        p1,
+                                        p2,
+                                        bits,
+                                        r,
+                                        c
+                                    );
                                 }
                             }
                         });
@@ -77,7 +89,7 @@ impl Grid {
         res
     }
 
-    fn find_y_wing_candidates(&self, curr: &Point, overlap: usize) -> Vec<&Point> {
+    pub(super) fn find_y_wing_candidates(&self, curr: &Point, overlap: usize) -> Vec<&Point> {
         let mut set = HashSet::<&Point>::new();
 
         for p in self.find_y_wing_candidates_unit(&BOX.cells[box_of(curr.0, curr.1)], curr, overlap) {
@@ -97,14 +109,14 @@ impl Grid {
 
     fn find_y_wing_candidates_unit<'a>(&self, unit: &'a [Point; 9], curr: &Point, overlap: usize) -> HashSet<&'a Point> {
         let mut set = HashSet::<&Point>::new();
-        let cell = self[&curr];
+        let cell = self[curr];
 
         for p in unit {
             if p == curr {
                 continue;
             }
 
-            let candidate = self[&p];
+            let candidate = self[p];
             if candidate.count() != 2 || (cell & candidate).count() != overlap {
                 continue;
             }
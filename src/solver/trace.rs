@@ -0,0 +1,109 @@
+/*
+ * Copyright © 2020, G.Ralph Kuntz, MD.
+ *
+ * Licensed under the Apache License, Version 2.0(the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIC
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::registry::{Registry, Strategy};
+use super::step::{deductions, record_notes, Deduction};
+use super::{Grid, Point};
+
+// A step is one deduction made while reducing a grid, numbered from 1, with the grid as it was after the deduction.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub number: usize,
+    pub deduction: Deduction,
+    pub grid: Grid,
+}
+
+// A trace is the ordered list of the steps made while reducing a grid.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+}
+
+impl Trace {
+    // record applies the strategy to the grid and adds a step for each pattern it finds. It returns true if it changes any cells.
+    pub(super) fn record(&mut self, grid: &mut Grid, strategy: &dyn Strategy, registry: &Registry) -> bool {
+        let before = *grid;
+        let (changed, notes) = record_notes(|| strategy.apply(grid, registry));
+        if changed {
+            for (deduction, after) in deductions(strategy, notes, &before, grid) {
+                self.steps.push(Step {
+                    number: self.steps.len() + 1,
+                    deduction,
+                    grid: after,
+                });
+            }
+        }
+        changed
+    }
+
+    // export formats the trace with one line per step: the step number, strategy, level, pattern cells, pattern digits, placements, eliminations and the grid after the step, separated by tabs. Cells are written as r1c1 (counting from 1), digits as 5, placements as r1c1=5 and eliminations as r1c1-5. The grid is written in the 729 character form of encode_candidates, so that traces can be compared line by line and any step can be loaded again.
+    pub fn export(&self) -> String {
+        let cell = |p: &Point| format!("r{}c{}", p.0 + 1, p.1 + 1);
+        let list = |items: Vec<String>| if items.is_empty() { "-".to_string() } else { items.join(",") };
+
+        let mut out = String::new();
+        for step in &self.steps {
+            let d = &step.deduction;
+            let pattern = list(d.pattern.iter().map(cell).collect());
+            let digits = list(d.digits.iter().map(|digit| digit.to_string()).collect());
+            let placements = list(d.placements.iter().map(|(p, digit)| format!("{}={}", cell(p), digit)).collect());
+            let eliminations = list(d.eliminations.iter().map(|(p, digit)| format!("{}-{}", cell(p), digit)).collect());
+
+            out += &format!(
+                "{}\t{}\t{:?}\t{}\t{}\t{}\t{}\t{}\n",
+                step.number,
+                d.strategy,
+                d.level,
+                pattern,
+                digits,
+                placements,
+                eliminations,
+                step.grid.encode_candidates()
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::marked;
+    use super::*;
+
+    #[test]
+    fn export() {
+        // The only 1 in row 0 is in (0, 0), and once it is placed naked_single clears it from box 0 and then column 0.
+        let marks: Vec<(Point, &str)> = (1..9).map(|c| ((0, c), "23456789")).collect();
+        let mut grid = marked(&marks);
+        let mut trace = Trace::default();
+        grid.reduce_with(&Registry::default(), &mut None, &mut Some(&mut trace));
+
+        let out = trace.export();
+        let lines: Vec<Vec<&str>> = out.lines().map(|line| line.split('\t').collect()).collect();
+        assert_eq!(lines.len(), trace.steps.len());
+        assert!(lines.len() >= 3);
+        assert!(lines.iter().all(|fields| fields.len() == 8));
+
+        let eliminations: Vec<String> = (2..=9).map(|d| format!("r1c1-{}", d)).collect();
+        assert_eq!(lines[0][..7], ["1", "hidden_single", "Easy", "r1c1", "1", "r1c1=1", eliminations.join(",").as_str()]);
+        assert_eq!(lines[0][7], trace.steps[0].grid.encode_candidates());
+        assert_eq!(lines[1][..6], ["2", "naked_single", "Easy", "r1c1", "1", "-"]);
+        assert_eq!(lines[1][6], "r2c1-1,r2c2-1,r2c3-1,r3c1-1,r3c2-1,r3c3-1");
+        assert_eq!(lines[2][..6], ["3", "naked_single", "Easy", "r1c1", "1", "-"]);
+        assert_eq!(lines[2][6].split(',').count(), 6);
+        assert_eq!(lines[lines.len() - 1][7], grid.encode_candidates());
+    }
+}